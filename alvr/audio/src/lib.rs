@@ -305,6 +305,8 @@ fn downmix_audio(data: Vec<u8>, in_channels: u16, out_channels: u16) -> Vec<u8>
 #[allow(unused_variables)]
 pub fn record_audio_blocking(
     is_running: Arc<dyn Fn() -> bool + Send + Sync>,
+    // Samples are discarded while muted, without stopping the recording
+    is_muted: Arc<dyn Fn() -> bool + Send + Sync>,
     mut sender: StreamSender<()>,
     device: &AudioDevice,
     channels_count: u16,
@@ -360,9 +362,11 @@ pub fn record_audio_blocking(
                 let data = downmix_audio(data, config.channels(), channels_count);
 
                 if is_running() {
-                    let mut buffer = sender.get_buffer(&()).unwrap();
-                    buffer.get_range_mut(0, data.len()).copy_from_slice(&data);
-                    sender.send(buffer).ok();
+                    if !is_muted() {
+                        let mut buffer = sender.get_buffer(&()).unwrap();
+                        buffer.get_range_mut(0, data.len()).copy_from_slice(&data);
+                        sender.send(buffer).ok();
+                    }
                 } else {
                     *state.lock() = AudioRecordState::ShouldStop;
                 }
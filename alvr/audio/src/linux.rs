@@ -219,6 +219,7 @@ fn pw_microphone_loop(
 
 pub fn record_audio_blocking_pipewire(
     is_running: Arc<dyn Fn() -> bool + Send + Sync>,
+    is_muted: Arc<dyn Fn() -> bool + Send + Sync>,
     sender: StreamSender<()>,
     channels_count: u16,
     sample_rate: u32,
@@ -245,6 +246,7 @@ pub fn record_audio_blocking_pipewire(
         pw_receiver,
         sender,
         is_running_clone_for_pw,
+        is_muted,
     ) {
         Ok(_) => {
             debug!("Pipewire loop exiting");
@@ -260,6 +262,7 @@ fn pw_audio_loop(
     pw_receiver: pw::channel::Receiver<Terminate>,
     mut sender: StreamSender<()>,
     is_running: Arc<dyn Fn() -> bool + Send + Sync>,
+    is_muted: Arc<dyn Fn() -> bool + Send + Sync>,
 ) -> Result<(), pw::Error> {
     debug!("Starting audio pw-thread");
 
@@ -316,7 +319,7 @@ fn pw_audio_loop(
                         }
                     }
                 }
-                if !final_buffer.is_empty() && is_running() {
+                if !final_buffer.is_empty() && is_running() && !is_muted() {
                     let mut buffer = sender.get_buffer(&()).unwrap();
                     buffer
                         .get_range_mut(0, final_buffer.len())
@@ -8,7 +8,10 @@ mod version;
 use once_cell::sync::Lazy;
 use parking_lot::{Condvar, Mutex, RwLockWriteGuard};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    fmt::{self, Display},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 pub use anyhow;
 pub use glam;
@@ -71,6 +74,32 @@ pub enum ConnectionState {
     Disconnecting,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisconnectReason {
+    // No packet received for KEEPALIVE_TIMEOUT
+    Timeout,
+    // The client closed the connection or a socket failed
+    ClientClosed,
+    // Incompatible protocol version
+    ProtocolError,
+    // Disconnected from the dashboard or the API
+    ServerKick,
+    ServerShutdown,
+}
+
+impl Display for DisconnectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            DisconnectReason::Timeout => "timed out",
+            DisconnectReason::ClientClosed => "closed by the client",
+            DisconnectReason::ProtocolError => "protocol error",
+            DisconnectReason::ServerKick => "kicked by the server",
+            DisconnectReason::ServerShutdown => "server shutting down",
+        };
+        write!(f, "{reason}")
+    }
+}
+
 pub fn wait_rwlock<T>(condvar: &Condvar, guard: &mut RwLockWriteGuard<'_, T>) {
     let staging_mutex = Mutex::<()>::new(());
     let mut inner_guard = staging_mutex.lock();
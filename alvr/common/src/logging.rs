@@ -1,8 +1,11 @@
 use anyhow::Result;
-use backtrace::Backtrace;
 use serde::{Deserialize, Serialize};
 use settings_schema::SettingsSchema;
-use std::{error::Error, fmt::Display};
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    error::Error,
+    fmt::Display,
+};
 
 pub const SERVER_IMPL_DBG_LABEL: &str = "SERVER IMPL";
 pub const CLIENT_IMPL_DBG_LABEL: &str = "CLIENT IMPL";
@@ -182,16 +185,42 @@ impl LogSeverity {
 pub struct LogEntry {
     pub severity: LogSeverity,
     pub content: String,
+    // Module path of the log call, like alvr_server_core::connection
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub thread: String,
+}
+
+// Unnamed threads are identified by their ID, like "ThreadId(7)"
+pub fn current_thread_name() -> String {
+    let thread = std::thread::current();
+
+    thread
+        .name()
+        .map(String::from)
+        .unwrap_or_else(|| format!("{:?}", thread.id()))
 }
 
 pub fn set_panic_hook() {
     std::panic::set_hook(Box::new(|panic_info| {
-        let err_str = format!(
-            "What happened:\n{panic_info}\n\nBacktrace:\n{:?}",
-            Backtrace::new()
-        );
+        // Always captured, a crash without a backtrace is hard to investigate. RUST_BACKTRACE=full
+        // only makes it more verbose
+        let backtrace = Backtrace::force_capture();
+        let backtrace_str = if backtrace.status() == BacktraceStatus::Captured {
+            if std::env::var("RUST_BACKTRACE").is_ok_and(|value| value == "full") {
+                format!("{backtrace:#}")
+            } else {
+                backtrace.to_string()
+            }
+        } else {
+            "Unsupported on this platform".into()
+        };
+        let err_str = format!("What happened:\n{panic_info}\n\nBacktrace:\n{backtrace_str}");
 
+        // The error sinks include the crash log, make sure it is written before the process exits
         log::error!("ALVR panicked: {err_str}");
+        log::logger().flush();
 
         #[cfg(all(not(target_os = "android"), not(target_os = "ios")))]
         std::thread::spawn({
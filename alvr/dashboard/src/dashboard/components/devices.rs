@@ -13,6 +13,7 @@ use eframe::{
 struct EditPopupState {
     new_devices: bool,
     hostname: String,
+    name: String,
     ips: Vec<String>,
 }
 
@@ -137,6 +138,12 @@ impl DevicesTab {
                             TextEdit::singleline(&mut state.hostname),
                         );
 
+                        ui[0].horizontal(|ui| {
+                            ui.add_space(5.0);
+                            ui.label("Name:");
+                        });
+                        ui[1].text_edit_singleline(&mut state.name);
+
                         ui[0].horizontal(|ui| {
                             ui.add_space(5.0);
                             ui.label("IP Addresses:");
@@ -160,7 +167,7 @@ impl DevicesTab {
 
                             if state.new_devices {
                                 requests.push(ServerRequest::UpdateClientList {
-                                    hostname: state.hostname,
+                                    hostname: state.hostname.clone(),
                                     action: ClientListAction::AddIfMissing {
                                         trusted: true,
                                         manual_ips,
@@ -168,10 +175,14 @@ impl DevicesTab {
                                 });
                             } else {
                                 requests.push(ServerRequest::UpdateClientList {
-                                    hostname: state.hostname,
+                                    hostname: state.hostname.clone(),
                                     action: ClientListAction::SetManualIps(manual_ips),
                                 });
                             }
+                            requests.push(ServerRequest::RenameClient {
+                                hostname: state.hostname,
+                                display_name: state.name,
+                            });
                         } else {
                             self.edit_popup_state = Some(state);
                         }
@@ -230,7 +241,7 @@ fn wired_client_section(
                         ui.end_row();
                     } else if let Some((_, data)) = maybe_client {
                         ui.horizontal(|ui| {
-                            ui.label(&data.display_name);
+                            ui.label(client_name(data));
                         });
                         ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                             connection_label(ui, &data.connection_state);
@@ -317,6 +328,7 @@ fn trusted_clients_section(
                         *edit_popup_state = Some(EditPopupState {
                             hostname: "XXXX.client.local.".into(),
                             new_devices: true,
+                            name: String::new(),
                             ips: Vec::new(),
                         });
                     }
@@ -332,7 +344,7 @@ fn trusted_clients_section(
                             .num_columns(2)
                             .spacing(egui::vec2(8.0, 8.0))
                             .show(ui, |ui| {
-                                ui.label(&data.display_name);
+                                ui.label(client_name(data));
                                 ui.horizontal(|ui| {
                                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                                         connection_label(ui, &data.connection_state)
@@ -357,6 +369,7 @@ fn trusted_clients_section(
                                         *edit_popup_state = Some(EditPopupState {
                                             new_devices: false,
                                             hostname: hostname.to_owned(),
+                                            name: data.custom_name.clone().unwrap_or_default(),
                                             ips: data
                                                 .manual_ips
                                                 .iter()
@@ -364,6 +377,15 @@ fn trusted_clients_section(
                                                 .collect::<Vec<String>>(),
                                         });
                                     }
+                                    if matches!(
+                                        data.connection_state,
+                                        ConnectionState::Connected | ConnectionState::Streaming
+                                    ) && ui.button("Reconnect").clicked()
+                                    {
+                                        request = Some(ServerRequest::ReconnectClient {
+                                            hostname: hostname.clone(),
+                                        });
+                                    }
                                 });
                             });
                     });
@@ -373,6 +395,10 @@ fn trusted_clients_section(
     request
 }
 
+fn client_name(data: &ClientConnectionConfig) -> &str {
+    data.custom_name.as_deref().unwrap_or(&data.display_name)
+}
+
 fn connection_label(ui: &mut Ui, connection_state: &ConnectionState) {
     match connection_state {
         ConnectionState::Disconnected => ui.colored_label(Color32::GRAY, "Disconnected"),
@@ -1,13 +1,10 @@
-use alvr_gui_common::theme;
-use alvr_packets::{FirewallRulesAction, ServerRequest};
+use alvr_gui_common::theme::{self, log_colors};
+use alvr_packets::{FirewallRulesAction, RegisteredDriver, ServerRequest};
 use eframe::{
     egui::{Frame, Grid, Layout, RichText, Ui},
     emath::Align,
 };
-use std::{
-    path::PathBuf,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
 const DRIVER_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
 
@@ -17,7 +14,7 @@ pub enum InstallationTabRequest {
 }
 
 pub struct InstallationTab {
-    drivers: Vec<PathBuf>,
+    drivers: Vec<RegisteredDriver>,
     last_update_instant: Instant,
 }
 
@@ -29,7 +26,7 @@ impl InstallationTab {
         }
     }
 
-    pub fn update_drivers(&mut self, list: Vec<PathBuf>) {
+    pub fn update_drivers(&mut self, list: Vec<RegisteredDriver>) {
         self.drivers = list;
     }
 
@@ -71,15 +68,18 @@ impl InstallationTab {
                     });
 
                     Grid::new(0).num_columns(2).show(ui, |ui| {
-                        for driver_path in &self.drivers {
+                        for driver in &self.drivers {
                             ui.horizontal(|ui| {
                                 ui.add_space(5.0);
-                                ui.label(driver_path.to_string_lossy());
+                                ui.label(driver.path.to_string_lossy());
+                                if !driver.exists {
+                                    ui.colored_label(log_colors::WARNING_LIGHT, "(missing)");
+                                }
                             });
                             ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                                 if ui.button("Remove").clicked() {
                                     requests.push(InstallationTabRequest::ServerRequest(
-                                        ServerRequest::UnregisterDriver(driver_path.clone()),
+                                        ServerRequest::UnregisterDriver(driver.path.clone()),
                                     ));
                                 }
                             });
@@ -1,11 +1,11 @@
-use alvr_common::{debug, error, info, parking_lot::Mutex, semver::Version, warn, RelaxedAtomic};
+use alvr_common::{debug, error, info, parking_lot::Mutex, warn, RelaxedAtomic};
 use alvr_events::{Event, EventType};
-use alvr_packets::ServerRequest;
+use alvr_packets::{ClientListAction, ServerRequest, ServerVersionInfo};
 use alvr_server_io::ServerSessionManager;
 use eframe::egui;
 use std::{
     io::ErrorKind,
-    net::{SocketAddr, TcpStream},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream},
     str::FromStr,
     sync::{mpsc, Arc},
     thread::{self, JoinHandle},
@@ -37,6 +37,7 @@ fn report_event_local(
         .send(PolledEvent {
             inner: Event {
                 timestamp: "".into(),
+                sequence: 0,
                 event_type,
             },
             from_dashboard: false,
@@ -85,7 +86,21 @@ impl DataSources {
         let server_connected = Arc::new(RelaxedAtomic::new(false));
 
         let session_manager = get_local_session_source();
-        let port = session_manager.settings().connection.web_server_port;
+        let connection = &session_manager.settings().connection;
+        // The server can't be reached on the unspecified address, fall back to localhost
+        let bind_ip = connection
+            .web_server_bind
+            .parse::<IpAddr>()
+            .ok()
+            .filter(|ip| !ip.is_unspecified())
+            .unwrap_or(Ipv4Addr::LOCALHOST.into());
+        let server_address = SocketAddr::new(bind_ip, connection.web_server_port);
+        let auth_header = session_manager
+            .settings()
+            .connection
+            .web_server_auth_token
+            .as_ref()
+            .map(|token| format!("Bearer {token}"));
         let session_source = Arc::new(Mutex::new(SessionSource::Local(Box::new(session_manager))));
 
         let requests_thread = thread::spawn({
@@ -93,8 +108,9 @@ impl DataSources {
             let context = context.clone();
             let session_source = Arc::clone(&session_source);
             let events_sender = events_sender.clone();
+            let auth_header = auth_header.clone();
             move || {
-                let uri = format!("http://127.0.0.1:{port}/api/dashboard-request");
+                let uri = format!("http://{server_address}/api/dashboard-request");
                 let request_agent: ureq::Agent = ureq::Agent::config_builder()
                     .timeout_global(Some(REQUEST_TIMEOUT))
                     .build()
@@ -114,7 +130,21 @@ impl DataSources {
                                     report_session_local(&context, &events_sender, session_manager);
                                 }
                                 ServerRequest::UpdateSession(session) => {
-                                    *session_manager.session_mut() = *session;
+                                    if let Err(e) = session_manager.update_session(*session, None) {
+                                        error!("{e}");
+                                    }
+
+                                    report_session_local(&context, &events_sender, session_manager);
+                                }
+                                ServerRequest::UpdateSessionChecked {
+                                    session,
+                                    expected_revision,
+                                } => {
+                                    if let Err(e) = session_manager
+                                        .update_session(*session, Some(expected_revision))
+                                    {
+                                        error!("{e}");
+                                    }
 
                                     report_session_local(&context, &events_sender, session_manager);
                                 }
@@ -125,12 +155,66 @@ impl DataSources {
 
                                     report_session_local(&context, &events_sender, session_manager);
                                 }
+                                ServerRequest::PatchSession {
+                                    patch,
+                                    expected_revision,
+                                } => {
+                                    if let Err(e) =
+                                        session_manager.patch_session(patch, expected_revision)
+                                    {
+                                        error!("{e}");
+                                    }
+
+                                    report_session_local(&context, &events_sender, session_manager);
+                                }
+                                ServerRequest::SetBitrate { mbps } => {
+                                    let mbps = session_manager.set_constant_bitrate(mbps);
+                                    info!("Bitrate for the next session set to {mbps} Mbps");
+
+                                    report_session_local(&context, &events_sender, session_manager);
+                                }
+                                ServerRequest::ResetSettings => {
+                                    session_manager.session_mut().session_settings =
+                                        alvr_session::session_settings_default();
+
+                                    report_session_local(&context, &events_sender, session_manager);
+                                }
+                                ServerRequest::ImportSession { session } => {
+                                    if let Err(e) = session_manager.import_session(session) {
+                                        error!("{e}");
+                                    }
+
+                                    report_session_local(&context, &events_sender, session_manager);
+                                }
                                 ServerRequest::UpdateClientList { hostname, action } => {
                                     session_manager.update_client_list(hostname, action);
 
                                     report_session_local(&context, &events_sender, session_manager);
                                 }
-                                ServerRequest::GetAudioDevices => {
+                                ServerRequest::RenameClient {
+                                    hostname,
+                                    display_name,
+                                } => {
+                                    let display_name = display_name.trim();
+                                    session_manager.update_client_list(
+                                        hostname,
+                                        ClientListAction::SetCustomName(
+                                            (!display_name.is_empty())
+                                                .then(|| display_name.to_owned()),
+                                        ),
+                                    );
+
+                                    report_session_local(&context, &events_sender, session_manager);
+                                }
+                                ServerRequest::SetAudioDevice { kind, id } => {
+                                    if let Err(e) = session_manager.set_audio_device(kind, id) {
+                                        warn!("Failed to set audio device: {e}");
+                                    }
+
+                                    report_session_local(&context, &events_sender, session_manager);
+                                }
+                                ServerRequest::GetAudioDevices
+                                | ServerRequest::RefreshAudioDevices => {
                                     if let Ok(list) = session_manager.get_audio_devices_list() {
                                         report_event_local(
                                             &context,
@@ -152,54 +236,105 @@ impl DataSources {
                                     let alvr_driver_dir =
                                         filesystem_layout.openvr_driver_root_dir.clone();
 
-                                    alvr_server_io::driver_registration(&[alvr_driver_dir], true)
-                                        .ok();
+                                    if let Err(e) = alvr_server_io::driver_registration(
+                                        &[alvr_driver_dir],
+                                        true,
+                                    ) {
+                                        error!("{e}");
+                                    }
 
-                                    if let Ok(list) = alvr_server_io::get_registered_drivers() {
+                                    if let Ok(list) = alvr_server_io::get_driver_list() {
                                         report_event_local(
                                             &context,
                                             &events_sender,
-                                            EventType::DriversList(list),
+                                            EventType::DriversStatus(list),
                                         )
                                     }
                                 }
                                 ServerRequest::UnregisterDriver(path) => {
-                                    alvr_server_io::driver_registration(&[path], false).ok();
+                                    if let Err(e) =
+                                        alvr_server_io::driver_registration(&[path], false)
+                                    {
+                                        error!("{e}");
+                                    }
 
-                                    if let Ok(list) = alvr_server_io::get_registered_drivers() {
+                                    if let Ok(list) = alvr_server_io::get_driver_list() {
                                         report_event_local(
                                             &context,
                                             &events_sender,
-                                            EventType::DriversList(list),
+                                            EventType::DriversStatus(list),
                                         )
                                     }
                                 }
                                 ServerRequest::GetDriverList => {
-                                    if let Ok(list) = alvr_server_io::get_registered_drivers() {
+                                    if let Ok(list) = alvr_server_io::get_driver_list() {
                                         report_event_local(
                                             &context,
                                             &events_sender,
-                                            EventType::DriversList(list),
+                                            EventType::DriversStatus(list),
                                         )
                                     }
                                 }
-                                ServerRequest::CaptureFrame
+                                ServerRequest::GetLogs { .. }
+                                | ServerRequest::SetLogLevel { .. }
+                                | ServerRequest::GetConnectionStatistics
+                                | ServerRequest::GetButtonPaths
+                                | ServerRequest::GetServerInfo
+                                | ServerRequest::GetSettingsSchema
+                                | ServerRequest::GetAvailableEncoders
+                                | ServerRequest::GetDiscoveryStatus
+                                | ServerRequest::GetStorageUsage
+                                | ServerRequest::GetClientHistory { .. }
+                                | ServerRequest::SetAudioState { .. }
+                                | ServerRequest::TriggerHaptics { .. }
+                                | ServerRequest::ReconnectClient { .. }
+                                | ServerRequest::GetFirewallStatus
+                                | ServerRequest::ExportSession
+                                | ServerRequest::CaptureFrame
                                 | ServerRequest::InsertIdr
+                                | ServerRequest::InsertIdrBurst { .. }
                                 | ServerRequest::StartRecording
+                                | ServerRequest::StartRecordingWithOptions(_)
                                 | ServerRequest::StopRecording => {
                                     warn!("Cannot perform action, streamer (SteamVR) is not connected.")
                                 }
-                                ServerRequest::RestartSteamvr | ServerRequest::ShutdownSteamvr => {
+                                ServerRequest::RestartSteamvr
+                                | ServerRequest::RestartServer
+                                | ServerRequest::ShutdownSteamvr
+                                | ServerRequest::ShutdownServer => {
                                     warn!("Streamer not launched, can't signal SteamVR shutdown")
                                 }
                             }
                         } else {
                             // todo: this should be changed to a GET request, requires removing body
-                            request_agent
-                                .post(&uri)
-                                .header("X-ALVR", "true")
-                                .send_json(&request)
-                                .ok();
+                            let mut http_request =
+                                request_agent.post(&uri).header("X-ALVR", "true");
+                            if let Some(auth_header) = &auth_header {
+                                http_request = http_request.header("Authorization", auth_header);
+                            }
+                            let response = http_request.send_json(&request).ok();
+
+                            // Replay the log history as if it was received from the events socket
+                            if let (ServerRequest::GetLogs { .. }, Some(response)) =
+                                (&request, response)
+                            {
+                                let events = response
+                                    .into_body()
+                                    .read_to_string()
+                                    .ok()
+                                    .and_then(|body| serde_json::from_str::<Vec<Event>>(&body).ok())
+                                    .unwrap_or_default();
+
+                                for event in events {
+                                    events_sender
+                                        .send(PolledEvent {
+                                            inner: event,
+                                            from_dashboard: false,
+                                        })
+                                        .ok();
+                                }
+                                context.request_repaint();
+                            }
                         }
                     }
 
@@ -211,6 +346,7 @@ impl DataSources {
         let events_thread = thread::spawn({
             let running = Arc::clone(&running);
             let session_source = Arc::clone(&session_source);
+            let auth_header = auth_header.clone();
             move || {
                 while running.value() {
                     if matches!(*session_source.lock(), SessionSource::Local(_)) {
@@ -219,12 +355,10 @@ impl DataSources {
                         continue;
                     }
 
-                    let uri = Uri::from_str(&format!("ws://127.0.0.1:{port}/api/events")).unwrap();
+                    let uri = Uri::from_str(&format!("ws://{server_address}/api/events")).unwrap();
 
-                    let maybe_socket = TcpStream::connect_timeout(
-                        &SocketAddr::from_str(&format!("127.0.0.1:{port}")).unwrap(),
-                        Duration::from_millis(500),
-                    );
+                    let maybe_socket =
+                        TcpStream::connect_timeout(&server_address, Duration::from_millis(500));
                     let Ok(socket) = maybe_socket else {
                         thread::sleep(Duration::from_millis(500));
 
@@ -234,6 +368,11 @@ impl DataSources {
                     let mut req = uri.into_client_request().unwrap();
                     req.headers_mut()
                         .insert("X-ALVR", HeaderValue::from_str("true").unwrap());
+                    if let Some(auth_header) = &auth_header {
+                        if let Ok(value) = HeaderValue::from_str(auth_header) {
+                            req.headers_mut().insert("Authorization", value);
+                        }
+                    }
 
                     let Ok((mut ws, _)) = tungstenite::client(req, socket) else {
                         thread::sleep(Duration::from_millis(500));
@@ -257,6 +396,11 @@ impl DataSources {
                                     context.request_repaint();
                                 }
                             }
+                            Ok(tungstenite::Message::Close(Some(frame))) => {
+                                info!("Server closed the events connection: {}", frame.reason);
+
+                                break;
+                            }
                             Err(e) => {
                                 if let tungstenite::Error::Io(e) = e {
                                     if e.kind() == ErrorKind::WouldBlock {
@@ -279,10 +423,11 @@ impl DataSources {
             let running = Arc::clone(&running);
             let session_source = Arc::clone(&session_source);
             let server_connected = Arc::clone(&server_connected);
+            let requests_sender = requests_sender.clone();
             move || {
                 const PING_INTERVAL: Duration = Duration::from_secs(1);
                 let mut deadline = Instant::now();
-                let uri = format!("http://127.0.0.1:{port}/api/version");
+                let uri = format!("http://{server_address}/api/version");
 
                 let request_agent: ureq::Agent = ureq::Agent::config_builder()
                     .timeout_global(Some(REQUEST_TIMEOUT))
@@ -290,16 +435,21 @@ impl DataSources {
                     .into();
 
                 loop {
-                    let maybe_server_version = request_agent
-                        .get(&uri)
-                        .header("X-ALVR", "true")
-                        .call()
+                    let mut version_request = request_agent.get(&uri).header("X-ALVR", "true");
+                    if let Some(auth_header) = &auth_header {
+                        version_request = version_request.header("Authorization", auth_header);
+                    }
+
+                    let maybe_server_version = version_request.call().ok().and_then(|r| {
+                        serde_json::from_str::<ServerVersionInfo>(
+                            &r.into_body().read_to_string().ok()?,
+                        )
                         .ok()
-                        .and_then(|r| {
-                            Version::from_str(&r.into_body().read_to_string().ok()?).ok()
-                        });
+                    });
 
-                    let connected = if let Some(version) = maybe_server_version {
+                    let connected = if let Some(ServerVersionInfo { version, .. }) =
+                        maybe_server_version
+                    {
                         // We need exact match because we don't do session extrapolation at the
                         // dashboard level. In the future we may relax the contraint and consider
                         // protocol compatibility check for dashboard.
@@ -319,6 +469,13 @@ impl DataSources {
                         if connected && matches!(*session_source_lock, SessionSource::Local(_)) {
                             info!("Server connected");
                             *session_source_lock = SessionSource::Remote;
+
+                            // Show what happened before the dashboard connected
+                            requests_sender
+                                .send(ServerRequest::GetLogs {
+                                    max_entries: usize::MAX,
+                                })
+                                .ok();
                         } else if !connected
                             && matches!(*session_source_lock, SessionSource::Remote)
                         {
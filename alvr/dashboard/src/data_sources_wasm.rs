@@ -33,11 +33,18 @@ impl DataSources {
 
     pub fn poll_event(&mut self) -> Option<Event> {
         if self.ws_receiver.is_none() {
-            let host = web_sys::window().unwrap().location().host().unwrap();
+            let location = web_sys::window().unwrap().location();
+            let host = location.host().unwrap();
+            let scheme = if location.protocol().unwrap() == "https:" {
+                "wss"
+            } else {
+                "ws"
+            };
             // TODO: Set X-ALVR
             //let mut options = ewebsock::Options::default();
             //options.additional_headers = vec!(("X-ALVR", "true"));
-            let Ok((_, receiver)) = ewebsock::connect(format!("ws://{host}/api/events")) else {
+            let Ok((_, receiver)) = ewebsock::connect(format!("{scheme}://{host}/api/events"))
+            else {
                 return None;
             };
             self.ws_receiver = Some(receiver);
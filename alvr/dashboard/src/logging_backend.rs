@@ -28,9 +28,12 @@ pub fn init_logging(event_sender: mpsc::Sender<PolledEvent>) {
                 .send(PolledEvent {
                     inner: Event {
                         timestamp: timestamp.clone(),
+                        sequence: 0,
                         event_type: EventType::Log(LogEntry {
                             severity: LogSeverity::from_log_level(record.level()),
                             content: format!("{}", record.args()),
+                            target: record.target().to_string(),
+                            thread: alvr_common::current_thread_name(),
                         }),
                     },
                     from_dashboard: true,
@@ -1,6 +1,6 @@
-use alvr_common::{info, DeviceMotion, LogEntry, LogSeverity, Pose};
-use alvr_packets::{AudioDevicesList, ButtonValue};
-use alvr_session::SessionConfig;
+use alvr_common::{glam::UVec2, info, DeviceMotion, DisconnectReason, LogEntry, LogSeverity, Pose};
+use alvr_packets::{AudioDevicesList, ButtonValue, RegisteredDriver};
+use alvr_session::{CodecType, H264Profile, SessionConfig};
 use serde::{Deserialize, Serialize};
 use std::{path::PathBuf, time::Duration};
 
@@ -81,11 +81,32 @@ pub struct AdbEvent {
     pub download_progress: f32,
 }
 
+// Settings in effect for a stream, after negotiation with the client capabilities
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StreamConfigSummary {
+    pub client_hostname: String,
+    pub codec: CodecType,
+    pub h264_profile: H264Profile,
+    pub bitrate: String,
+    pub view_resolution: UVec2,
+    pub target_view_resolution: UVec2,
+    pub refresh_rate: f32,
+    pub foveated_encoding: bool,
+    pub use_10bit: bool,
+    pub full_range: bool,
+    pub hdr: bool,
+    pub game_audio_sample_rate: u32,
+    pub wired: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "id", content = "data")]
 pub enum EventType {
     Log(LogEntry),
-    DebugGroup { group: String, message: String },
+    DebugGroup {
+        group: String,
+        message: String,
+    },
     Session(Box<SessionConfig>),
     StatisticsSummary(StatisticsSummary),
     GraphStatistics(GraphStatistics),
@@ -94,13 +115,57 @@ pub enum EventType {
     Haptics(HapticsEvent),
     AudioDevices(AudioDevicesList),
     DriversList(Vec<PathBuf>),
+    // Same as DriversList, also flagging the drivers whose directory was deleted
+    DriversStatus(Vec<RegisteredDriver>),
     ServerRequestsSelfRestart,
     Adb(AdbEvent),
+    StreamConfig(Box<StreamConfigSummary>),
+    ClientDisconnected {
+        hostname: String,
+        reason: DisconnectReason,
+    },
+    AudioState {
+        game_audio_muted: bool,
+        microphone_muted: bool,
+    },
+    // Sent instead of a whole Session event, since it is updated periodically while streaming
+    ClientLastSeen {
+        hostname: String,
+        last_seen_ms: u64,
+    },
+}
+
+impl EventType {
+    // Matches the serialized variant name
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventType::Log(_) => "Log",
+            EventType::DebugGroup { .. } => "DebugGroup",
+            EventType::Session(_) => "Session",
+            EventType::StatisticsSummary(_) => "StatisticsSummary",
+            EventType::GraphStatistics(_) => "GraphStatistics",
+            EventType::Tracking(_) => "Tracking",
+            EventType::Buttons(_) => "Buttons",
+            EventType::Haptics(_) => "Haptics",
+            EventType::AudioDevices(_) => "AudioDevices",
+            EventType::DriversList(_) => "DriversList",
+            EventType::DriversStatus(_) => "DriversStatus",
+            EventType::ServerRequestsSelfRestart => "ServerRequestsSelfRestart",
+            EventType::Adb(_) => "Adb",
+            EventType::StreamConfig(_) => "StreamConfig",
+            EventType::ClientDisconnected { .. } => "ClientDisconnected",
+            EventType::AudioState { .. } => "AudioState",
+            EventType::ClientLastSeen { .. } => "ClientLastSeen",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Event {
     pub timestamp: String,
+    // Incremented for each event sent by the server, so clients can detect dropped events
+    #[serde(default)]
+    pub sequence: u64,
     pub event_type: EventType,
 }
 
@@ -122,8 +187,13 @@ impl Event {
             EventType::Haptics(_) => "HAPTICS".to_string(),
             EventType::AudioDevices(_) => "AUDIO DEV".to_string(),
             EventType::DriversList(_) => "DRV LIST".to_string(),
+            EventType::DriversStatus(_) => "DRV STATUS".to_string(),
             EventType::ServerRequestsSelfRestart => "RESTART".to_string(),
             EventType::Adb(_) => "ADB".to_string(),
+            EventType::StreamConfig(_) => "STREAM CFG".to_string(),
+            EventType::ClientDisconnected { .. } => "DISCONNECT".to_string(),
+            EventType::AudioState { .. } => "AUDIO".to_string(),
+            EventType::ClientLastSeen { .. } => "LAST SEEN".to_string(),
         }
     }
 
@@ -138,8 +208,21 @@ impl Event {
             EventType::Haptics(haptics) => serde_json::to_string(haptics).unwrap(),
             EventType::AudioDevices(devices) => serde_json::to_string(devices).unwrap(),
             EventType::DriversList(drivers) => serde_json::to_string(drivers).unwrap(),
+            EventType::DriversStatus(drivers) => serde_json::to_string(drivers).unwrap(),
             EventType::ServerRequestsSelfRestart => "Request for server restart".into(),
             EventType::Adb(adb) => serde_json::to_string(adb).unwrap(),
+            EventType::StreamConfig(config) => serde_json::to_string(config).unwrap(),
+            EventType::ClientDisconnected { hostname, reason } => format!("{hostname}: {reason}"),
+            EventType::AudioState {
+                game_audio_muted,
+                microphone_muted,
+            } => format!(
+                "Game audio muted: {game_audio_muted}, microphone muted: {microphone_muted}"
+            ),
+            EventType::ClientLastSeen {
+                hostname,
+                last_seen_ms,
+            } => format!("{hostname}: {last_seen_ms}"),
         }
     }
 }
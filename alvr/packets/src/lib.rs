@@ -2,7 +2,7 @@ use alvr_common::{
     anyhow::Result,
     glam::{UVec2, Vec2},
     semver::Version,
-    ConnectionState, DeviceMotion, Fov, LogEntry, LogSeverity, Pose, ToAny,
+    ConnectionState, DeviceMotion, DisconnectReason, Fov, LogEntry, LogSeverity, Pose, ToAny,
 };
 use alvr_session::{
     ClientsidePostProcessingConfig, CodecType, PassthroughMode, SessionConfig, Settings,
@@ -310,6 +310,14 @@ pub struct AudioDevicesList {
     pub input: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum AudioDeviceKind {
+    // Game audio
+    Output,
+    // Microphone source, set as the default SteamVR microphone
+    Input,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub enum PathSegment {
     Name(String),
@@ -354,12 +362,21 @@ pub enum ClientListAction {
         trusted: bool,
         manual_ips: Vec<IpAddr>,
     },
+    // Registers a client reachable at a static IP, for networks where discovery doesn't work
+    AddManual {
+        ip: IpAddr,
+        trusted: bool,
+    },
     SetDisplayName(String),
+    SetCustomName(Option<String>),
     Trust,
     SetManualIps(Vec<IpAddr>),
     RemoveEntry,
     UpdateCurrentIp(Option<IpAddr>),
+    UpdateLastSeen(u64),
     SetConnectionState(ConnectionState),
+    // Recorded in the connection history entry of the current connection
+    SetDisconnectReason(DisconnectReason),
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -379,33 +396,142 @@ pub struct PathValuePair {
     pub value: json::Value,
 }
 
+// Entry of the SteamVR external drivers list
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegisteredDriver {
+    pub path: PathBuf,
+    pub exists: bool,
+}
+
+// Recordings are saved as the elementary stream produced by the encoder, without audio
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RecordingOptions {
+    // Directory of the recording files, defaults to the log directory
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum FirewallRulesAction {
     Add,
     Remove,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FirewallStatus {
+    Active,
+    Inactive,
+    // The status can only be read with admin privileges
+    RequiresAdmin,
+    NoFirewall,
+    Unknown,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ServerRequest {
     Log(LogEntry),
+    GetLogs {
+        max_entries: usize,
+    },
+    SetLogLevel {
+        level: String,
+    },
     GetSession,
+    // Types, defaults, ranges and help text of the settings, to generate settings UIs
+    GetSettingsSchema,
     UpdateSession(Box<SessionConfig>),
+    // Rejected if the session was modified since expected_revision
+    UpdateSessionChecked {
+        session: Box<SessionConfig>,
+        expected_revision: u64,
+    },
     SetValues(Vec<PathValuePair>),
+    // JSON merge patch (RFC 7386) applied to the session. Null values remove fields
+    PatchSession {
+        patch: json::Value,
+        #[serde(default)]
+        expected_revision: Option<u64>,
+    },
+    ResetSettings,
+    ExportSession,
+    ImportSession {
+        session: json::Value,
+    },
     UpdateClientList {
         hostname: String,
         action: ClientListAction,
     },
+    ReconnectClient {
+        hostname: String,
+    },
+    RenameClient {
+        hostname: String,
+        display_name: String,
+    },
+    GetClientHistory {
+        hostname: String,
+    },
     GetAudioDevices,
+    RefreshAudioDevices,
+    // The id is a device name from the audio devices list
+    SetAudioDevice {
+        kind: AudioDeviceKind,
+        id: String,
+    },
+    // None leaves the state unchanged
+    SetAudioState {
+        game_audio_muted: Option<bool>,
+        microphone_muted: Option<bool>,
+    },
+    GetConnectionStatistics,
+    SetBitrate {
+        mbps: u64,
+    },
+    GetButtonPaths,
+    // OS, CPU, GPU and build details, for bug reports
+    GetServerInfo,
+    GetAvailableEncoders,
+    GetDiscoveryStatus,
+    // Bytes used by the log directory and the recordings, and free space of their volumes
+    GetStorageUsage,
+    TriggerHaptics {
+        path: String,
+        duration_ms: u64,
+        frequency: f32,
+        amplitude: f32,
+    },
+    // Saves the next input and output frames as PNG files and returns their paths. Only supported
+    // on Linux, the request fails on other platforms
     CaptureFrame,
     InsertIdr,
+    InsertIdrBurst {
+        count: u32,
+        interval_ms: u64,
+    },
     StartRecording,
+    StartRecordingWithOptions(RecordingOptions),
     StopRecording,
     FirewallRules(FirewallRulesAction),
+    GetFirewallStatus,
     RegisterAlvrDriver,
     UnregisterDriver(PathBuf),
     GetDriverList,
     RestartSteamvr,
     ShutdownSteamvr,
+    // Closes the websockets, finalizes the recording and flushes the logs before shutting down. The
+    // server runs inside the host process (SteamVR), which is shut down too
+    ShutdownServer,
+    // Same as ShutdownServer, then SteamVR is started again, which relaunches the server
+    RestartServer,
+}
+
+// Returned by the /api/version endpoint
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ServerVersionInfo {
+    pub version: Version,
+    pub git_commit: Option<String>,
+    pub protocol_id: String,
+    pub target_os: String,
 }
 
 // Note: server sends a packet to the client at low frequency, binary encoding, without ensuring
@@ -446,3 +572,18 @@ pub struct ViewParams {
     pub pose: Pose,
     pub fov: Fov,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_unchecked_session_update() {
+        let payload = json::json!({ "UpdateSession": SessionConfig::default() });
+
+        assert!(matches!(
+            json::from_value(payload).unwrap(),
+            ServerRequest::UpdateSession(_)
+        ));
+    }
+}
@@ -0,0 +1,16 @@
+use std::process::Command;
+
+fn main() {
+    let maybe_commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+
+    if let Some(commit) = maybe_commit {
+        println!("cargo:rustc-env=ALVR_GIT_COMMIT={}", commit.trim());
+    }
+
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+}
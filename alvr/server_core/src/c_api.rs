@@ -343,7 +343,7 @@ pub unsafe extern "C" fn alvr_poll_event(out_event: *mut AlvrEvent, timeout_ns:
                     *out_event = AlvrEvent::ButtonsUpdated;
                 }
                 ServerCoreEvent::RequestIDR => *out_event = AlvrEvent::RequestIDR,
-                ServerCoreEvent::CaptureFrame => *out_event = AlvrEvent::CaptureFrame,
+                ServerCoreEvent::CaptureFrame { .. } => *out_event = AlvrEvent::CaptureFrame,
                 ServerCoreEvent::RestartPending => {
                     *out_event = AlvrEvent::RestartPending;
                 }
@@ -351,6 +351,7 @@ pub unsafe extern "C" fn alvr_poll_event(out_event: *mut AlvrEvent, timeout_ns:
                     *out_event = AlvrEvent::ShutdownPending;
                 }
                 ServerCoreEvent::GameRenderLatencyFeedback(_)
+                | ServerCoreEvent::CapturePreviewFrame
                 | ServerCoreEvent::SetOpenvrProperty { .. } => {} // implementation not needed
             }
 
@@ -14,18 +14,18 @@ use alvr_common::{
     info,
     parking_lot::{Condvar, Mutex, RwLock},
     settings_schema::Switch,
-    warn, AnyhowToCon, ConResult, ConnectionError, ConnectionState, LifecycleState, Pose,
-    BUTTON_INFO, CONTROLLER_PROFILE_INFO, QUEST_CONTROLLER_PROFILE_PATH,
+    warn, AnyhowToCon, ConResult, ConnectionError, ConnectionState, DisconnectReason,
+    LifecycleState, Pose, BUTTON_INFO, CONTROLLER_PROFILE_INFO, QUEST_CONTROLLER_PROFILE_PATH,
 };
-use alvr_events::{AdbEvent, ButtonEvent, EventType};
+use alvr_events::{AdbEvent, ButtonEvent, EventType, StreamConfigSummary};
 use alvr_packets::{
     ClientConnectionResult, ClientControlPacket, ClientListAction, ClientStatistics,
     NegotiatedStreamingConfig, RealTimeConfig, ReservedClientControlPacket, ServerControlPacket,
     Tracking, VideoPacketHeader, AUDIO, HAPTICS, STATISTICS, TRACKING, VIDEO,
 };
 use alvr_session::{
-    BodyTrackingBDConfig, BodyTrackingSinkConfig, CodecType, ControllersEmulationMode, FrameSize,
-    H264Profile, OpenvrConfig, SessionConfig, SocketProtocol,
+    BitrateMode, BodyTrackingBDConfig, BodyTrackingSinkConfig, CodecType, ControllersEmulationMode,
+    FrameSize, H264Profile, OpenvrConfig, SessionConfig, SocketProtocol,
 };
 use alvr_sockets::{
     PeerType, ProtoControlSocket, StreamSocketBuilder, CONTROL_PORT, KEEPALIVE_INTERVAL,
@@ -35,15 +35,20 @@ use std::{
     collections::HashMap,
     net::{IpAddr, Ipv4Addr},
     process::Command,
-    sync::{mpsc::RecvTimeoutError, Arc},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc,
+    },
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 const RETRY_CONNECT_MIN_INTERVAL: Duration = Duration::from_secs(1);
 const HANDSHAKE_ACTION_TIMEOUT: Duration = Duration::from_secs(2);
 pub const STREAMING_RECV_TIMEOUT: Duration = Duration::from_millis(500);
 const REAL_TIME_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+const LAST_SEEN_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
 
 const MAX_UNREAD_PACKETS: usize = 10; // Applies per stream
 
@@ -56,6 +61,21 @@ fn align32(value: f32) -> u32 {
     ((value / 32.).floor() * 32.) as u32
 }
 
+// Only the first reason is kept, the other threads usually fail as a consequence
+fn set_disconnect_reason(
+    disconnect_reason: &Mutex<Option<DisconnectReason>>,
+    reason: DisconnectReason,
+) {
+    disconnect_reason.lock().get_or_insert(reason);
+}
+
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
 fn is_streaming(client_hostname: &str) -> bool {
     SESSION_MANAGER
         .read()
@@ -478,14 +498,32 @@ fn try_connect(
     ctx.connection_threads.lock().push(thread::spawn({
         let ctx = Arc::clone(&ctx);
         move || {
-            if let Err(e) = connection_pipeline(
+            let disconnect_reason = match connection_pipeline(
                 Arc::clone(&ctx),
                 lifecycle_state,
                 proto_socket,
                 client_hostname.clone(),
                 client_ip,
             ) {
-                error!("Handshake error for {client_hostname}: {e}");
+                Ok(reason) => reason,
+                Err(e) => {
+                    error!("Handshake error for {client_hostname}: {e}");
+
+                    None
+                }
+            };
+
+            if let Some(reason) = disconnect_reason {
+                info!("Client {client_hostname} disconnected: {reason}");
+
+                SESSION_MANAGER.write().update_client_list(
+                    client_hostname.clone(),
+                    ClientListAction::SetDisconnectReason(reason),
+                );
+                alvr_events::send_event(EventType::ClientDisconnected {
+                    hostname: client_hostname.clone(),
+                    reason,
+                });
             }
 
             let mut clients_to_be_removed = ctx.clients_to_be_removed.lock();
@@ -512,7 +550,7 @@ fn connection_pipeline(
     mut proto_socket: ProtoControlSocket,
     client_hostname: String,
     client_ip: IpAddr,
-) -> ConResult {
+) -> ConResult<Option<DisconnectReason>> {
     dbg_connection!("connection_pipeline: Begin");
 
     // This session lock will make sure settings and client list cannot be changed while connecting
@@ -531,6 +569,8 @@ fn connection_pipeline(
     );
 
     let disconnect_notif = Arc::new(Condvar::new());
+    let disconnect_reason = Arc::new(Mutex::new(None));
+    let last_seen_ms = Arc::new(AtomicU64::new(unix_time_ms()));
 
     dbg_connection!("connection_pipeline: Getting client status packet");
     let connection_result = match proto_socket.recv(HANDSHAKE_ACTION_TIMEOUT) {
@@ -538,7 +578,7 @@ fn connection_pipeline(
         Err(ConnectionError::TryAgain(e)) => {
             debug!("Failed to recive client connection packet. This is normal for USB connection.\n{e}");
 
-            return Ok(());
+            return Ok(None);
         }
         Err(e) => return Err(e),
     };
@@ -562,13 +602,13 @@ fn connection_pipeline(
                 client_protocol_id,
             );
 
-            return Ok(());
+            return Ok(Some(DisconnectReason::ProtocolError));
         }
 
         streaming_capabilities
     } else {
         debug!("Found client in standby. Retrying");
-        return Ok(());
+        return Ok(None);
     };
 
     let streaming_caps = if let Some(streaming_caps) = maybe_streaming_caps {
@@ -784,6 +824,25 @@ fn connection_pipeline(
     .to_con()?;
     proto_socket.send(&stream_config_packet).to_con()?;
 
+    alvr_events::send_event(EventType::StreamConfig(Box::new(StreamConfigSummary {
+        client_hostname: client_hostname.clone(),
+        codec,
+        h264_profile: encoder_profile,
+        bitrate: match &initial_settings.video.bitrate.mode {
+            BitrateMode::ConstantMbps(mbps) => format!("Constant {mbps} Mbps"),
+            BitrateMode::Adaptive { .. } => "Adaptive".into(),
+        },
+        view_resolution: stream_view_resolution,
+        target_view_resolution,
+        refresh_rate: fps,
+        foveated_encoding: enable_foveated_encoding,
+        use_10bit: enable_10_bits_encoding,
+        full_range: use_full_range,
+        hdr: enable_hdr,
+        game_audio_sample_rate,
+        wired,
+    })));
+
     let (mut control_sender, mut control_receiver) =
         proto_socket.split(STREAMING_RECV_TIMEOUT).to_con()?;
 
@@ -891,8 +950,11 @@ fn connection_pipeline(
     let game_audio_thread = if let Switch::Enabled(config) =
         initial_settings.audio.game_audio.clone()
     {
-        #[cfg(windows)]
         let ctx = Arc::clone(&ctx);
+        let is_muted = Arc::new({
+            let ctx = Arc::clone(&ctx);
+            move || ctx.game_audio_muted.load(Ordering::Relaxed)
+        });
 
         let client_hostname = client_hostname.clone();
         thread::spawn(move || {
@@ -903,6 +965,7 @@ fn connection_pipeline(
                         let client_hostname = client_hostname.clone();
                         move || is_streaming(&client_hostname)
                     }),
+                    is_muted.clone(),
                     game_audio_sender.clone(),
                     2,
                     game_audio_sample_rate,
@@ -912,7 +975,18 @@ fn connection_pipeline(
 
                 #[cfg(not(target_os = "linux"))]
                 {
-                    let device = match alvr_audio::AudioDevice::new_output(config.device.as_ref()) {
+                    let device_generation =
+                        ctx.game_audio_device_generation.load(Ordering::Relaxed);
+                    // Read at each restart since it can be changed with SetAudioDevice
+                    let device_config = SESSION_MANAGER
+                        .read()
+                        .settings()
+                        .audio
+                        .game_audio
+                        .as_option()
+                        .map_or_else(|| config.device.clone(), |config| config.device.clone());
+
+                    let device = match alvr_audio::AudioDevice::new_output(device_config.as_ref()) {
                         Ok(data) => data,
                         Err(e) => {
                             warn!("New audio device failed: {e:?}");
@@ -939,9 +1013,15 @@ fn connection_pipeline(
 
                     if let Err(e) = alvr_audio::record_audio_blocking(
                         Arc::new({
+                            let ctx = Arc::clone(&ctx);
                             let client_hostname = client_hostname.clone();
-                            move || is_streaming(&client_hostname)
+                            move || {
+                                is_streaming(&client_hostname)
+                                    && ctx.game_audio_device_generation.load(Ordering::Relaxed)
+                                        == device_generation
+                            }
                         }),
+                        is_muted.clone(),
                         game_audio_sender.clone(),
                         &device,
                         2,
@@ -992,31 +1072,43 @@ fn connection_pipeline(
                     .ok();
             }
 
+            let ctx = Arc::clone(&ctx);
             let client_hostname = client_hostname.clone();
             thread::spawn(move || {
-                #[cfg(not(target_os = "linux"))]
-                alvr_common::show_err(alvr_audio::play_audio_loop(
-                    {
-                        let client_hostname = client_hostname.clone();
-                        move || is_streaming(&client_hostname)
-                    },
-                    &sink,
-                    1,
-                    streaming_caps.microphone_sample_rate,
-                    config.buffering,
-                    &mut microphone_receiver,
-                ));
-                #[cfg(target_os = "linux")]
-                alvr_common::show_err(alvr_audio::linux::play_microphone_loop_pipewire(
-                    {
+                while is_streaming(&client_hostname) {
+                    // Discard the samples received while muted
+                    if ctx.microphone_muted.load(Ordering::Relaxed) {
+                        microphone_receiver.recv(STREAMING_RECV_TIMEOUT).ok();
+                        continue;
+                    }
+
+                    let is_running = {
+                        let ctx = Arc::clone(&ctx);
                         let client_hostname = client_hostname.clone();
-                        move || is_streaming(&client_hostname)
-                    },
-                    1,
-                    streaming_caps.microphone_sample_rate,
-                    config.buffering,
-                    &mut microphone_receiver,
-                ));
+                        move || {
+                            is_streaming(&client_hostname)
+                                && !ctx.microphone_muted.load(Ordering::Relaxed)
+                        }
+                    };
+
+                    #[cfg(not(target_os = "linux"))]
+                    alvr_common::show_err(alvr_audio::play_audio_loop(
+                        is_running,
+                        &sink,
+                        1,
+                        streaming_caps.microphone_sample_rate,
+                        config.buffering.clone(),
+                        &mut microphone_receiver,
+                    ));
+                    #[cfg(target_os = "linux")]
+                    alvr_common::show_err(alvr_audio::linux::play_microphone_loop_pipewire(
+                        is_running,
+                        1,
+                        streaming_caps.microphone_sample_rate,
+                        config.buffering.clone(),
+                        &mut microphone_receiver,
+                    ));
+                }
             })
         } else {
             thread::spawn(|| ())
@@ -1104,11 +1196,13 @@ fn connection_pipeline(
     let keepalive_thread = thread::spawn({
         let control_sender = Arc::clone(&control_sender);
         let disconnect_notif = Arc::clone(&disconnect_notif);
+        let disconnect_reason = Arc::clone(&disconnect_reason);
         let client_hostname = client_hostname.clone();
         move || {
             while is_streaming(&client_hostname) {
                 if let Err(e) = control_sender.lock().send(&ServerControlPacket::KeepAlive) {
                     info!("Client disconnected. Cause: {e:?}");
+                    set_disconnect_reason(&disconnect_reason, DisconnectReason::ClientClosed);
 
                     disconnect_notif.notify_one();
 
@@ -1144,8 +1238,16 @@ fn connection_pipeline(
         });
         let controllers_emulation_mode =
             controllers_config.map(|config| config.emulation_mode.clone());
+        let osc_output_sink = session_manager_lock
+            .settings()
+            .headset
+            .osc_output
+            .as_option()
+            .and_then(|config| tracking::OscOutputSink::new(config).ok());
 
         let disconnect_notif = Arc::clone(&disconnect_notif);
+        let disconnect_reason = Arc::clone(&disconnect_reason);
+        let last_seen_ms = Arc::clone(&last_seen_ms);
         let control_sender = Arc::clone(&control_sender);
         let client_hostname = client_hostname.clone();
         move || {
@@ -1156,6 +1258,7 @@ fn connection_pipeline(
                     Err(ConnectionError::TryAgain(_)) => {
                         if Instant::now() > disconnection_deadline {
                             info!("Client disconnected. Timeout");
+                            set_disconnect_reason(&disconnect_reason, DisconnectReason::Timeout);
                             break;
                         } else {
                             continue;
@@ -1163,9 +1266,11 @@ fn connection_pipeline(
                     }
                     Err(e) => {
                         info!("Client disconnected. Cause: {e}");
+                        set_disconnect_reason(&disconnect_reason, DisconnectReason::ClientClosed);
                         break;
                     }
                 };
+                last_seen_ms.store(unix_time_ms(), Ordering::Relaxed);
 
                 match packet {
                     ClientControlPacket::PlayspaceSync(packet) => {
@@ -1269,6 +1374,10 @@ fn connection_pipeline(
                                 .collect::<Vec<_>>();
 
                             if !button_entries.is_empty() {
+                                if let Some(sink) = &osc_output_sink {
+                                    sink.send_buttons(&button_entries);
+                                }
+
                                 ctx.events_sender
                                     .send(ServerCoreEvent::Buttons(button_entries))
                                     .ok();
@@ -1349,14 +1458,17 @@ fn connection_pipeline(
 
     let stream_receive_thread = thread::spawn({
         let disconnect_notif = Arc::clone(&disconnect_notif);
+        let disconnect_reason = Arc::clone(&disconnect_reason);
+        let last_seen_ms = Arc::clone(&last_seen_ms);
         let client_hostname = client_hostname.clone();
         move || {
             while is_streaming(&client_hostname) {
                 match stream_socket.recv() {
-                    Ok(()) => (),
+                    Ok(()) => last_seen_ms.store(unix_time_ms(), Ordering::Relaxed),
                     Err(ConnectionError::TryAgain(_)) => continue,
                     Err(e) => {
                         info!("Client disconnected. Cause: {e}");
+                        set_disconnect_reason(&disconnect_reason, DisconnectReason::ClientClosed);
 
                         disconnect_notif.notify_one();
 
@@ -1369,8 +1481,11 @@ fn connection_pipeline(
 
     let lifecycle_check_thread = thread::spawn({
         let disconnect_notif = Arc::clone(&disconnect_notif);
+        let disconnect_reason = Arc::clone(&disconnect_reason);
+        let last_seen_ms = Arc::clone(&last_seen_ms);
         let client_hostname = client_hostname.clone();
         move || {
+            let mut last_seen_update_deadline = Instant::now();
             while SESSION_MANAGER
                 .read()
                 .client_list()
@@ -1378,9 +1493,25 @@ fn connection_pipeline(
                 .is_some_and(|c| c.connection_state == ConnectionState::Streaming)
                 && *lifecycle_state.read() == LifecycleState::Resumed
             {
+                if Instant::now() > last_seen_update_deadline {
+                    SESSION_MANAGER.write().update_client_list(
+                        client_hostname.clone(),
+                        ClientListAction::UpdateLastSeen(last_seen_ms.load(Ordering::Relaxed)),
+                    );
+
+                    last_seen_update_deadline = Instant::now() + LAST_SEEN_UPDATE_INTERVAL;
+                }
+
                 thread::sleep(STREAMING_RECV_TIMEOUT);
             }
 
+            let reason = if *lifecycle_state.read() == LifecycleState::Resumed {
+                DisconnectReason::ServerKick
+            } else {
+                DisconnectReason::ServerShutdown
+            };
+            set_disconnect_reason(&disconnect_reason, reason);
+
             disconnect_notif.notify_one()
         }
     });
@@ -1401,9 +1532,13 @@ fn connection_pipeline(
         }
     }
 
+    *ctx.recording_client_hostname.lock() = Some(client_hostname.clone());
+
     if initial_settings.extra.capture.startup_video_recording {
         info!("Creating recording file");
-        crate::create_recording_file(&ctx, session_manager_lock.settings());
+        if let Err(e) = crate::create_recording_file(&ctx, session_manager_lock.settings()) {
+            error!("Failed to record video on disk: {e:?}");
+        }
     }
 
     session_manager_lock.update_client_list(
@@ -1423,7 +1558,18 @@ fn connection_pipeline(
     *ctx.video_channel_sender.lock() = None;
     *ctx.haptics_sender.lock() = None;
 
-    *ctx.video_recording_file.lock() = None;
+    if session_manager_lock
+        .settings()
+        .extra
+        .capture
+        .stop_recording_on_disconnect
+    {
+        if let Some(recording) = ctx.video_recording_file.lock().take() {
+            recording.finish();
+
+            info!("Video recording stopped because the client disconnected");
+        }
+    }
 
     session_manager_lock.update_client_list(
         client_hostname,
@@ -1473,5 +1619,8 @@ fn connection_pipeline(
 
     dbg_connection!("connection_pipeline: End");
 
-    Ok(())
+    let reason = disconnect_reason
+        .lock()
+        .unwrap_or(DisconnectReason::ServerKick);
+    Ok(Some(reason))
 }
@@ -4,11 +4,18 @@ mod connection;
 mod hand_gestures;
 mod haptics;
 mod input_mapping;
+mod log_rotation;
 mod logging_backend;
+mod mqtt;
+mod remote_log;
+mod server_info;
 mod sockets;
 mod statistics;
+mod storage;
 mod tracking;
+mod video_preview;
 mod web_server;
+mod webhook;
 
 pub use c_api::*;
 pub use logging_backend::init_logging;
@@ -16,8 +23,10 @@ pub use tracking::HandType;
 
 use crate::connection::VideoPacket;
 use alvr_common::{
+    anyhow::{bail, Context, Result},
     dbg_server_core, error,
     glam::Vec2,
+    info,
     once_cell::sync::Lazy,
     parking_lot::{Mutex, RwLock},
     settings_schema::Switch,
@@ -31,26 +40,32 @@ use alvr_packets::{
     VideoPacketHeader,
 };
 use alvr_server_io::ServerSessionManager;
-use alvr_session::{CodecType, OpenvrProperty, Settings};
+use alvr_session::{CaptureConfig, CodecType, OpenvrProperty, RecordingCleanupConfig, Settings};
 use alvr_sockets::StreamSender;
 use bitrate::{BitrateManager, DynamicEncoderParams};
+use bytes::{BufMut, Bytes, BytesMut};
 use statistics::StatisticsManager;
 use std::{
     collections::HashSet,
     env,
     ffi::OsStr,
-    fs::File,
-    io::Write,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         mpsc::{self, SyncSender, TrySendError},
         Arc, OnceLock,
     },
     thread::{self, JoinHandle},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::{
+    runtime::{self, Runtime},
+    sync::broadcast,
 };
-use tokio::{runtime::Runtime, sync::broadcast};
 use tracking::TrackingManager;
+use video_preview::VideoPreview;
 
 static FILESYSTEM_LAYOUT: OnceLock<afs::Layout> = OnceLock::new();
 
@@ -59,9 +74,14 @@ static FILESYSTEM_LAYOUT: OnceLock<afs::Layout> = OnceLock::new();
 // NB: this must remain a global because only one instance should exist for the whole application
 // execution time.
 static SESSION_MANAGER: Lazy<RwLock<ServerSessionManager>> = Lazy::new(|| {
-    RwLock::new(ServerSessionManager::new(
-        FILESYSTEM_LAYOUT.get().map(|l| l.session()),
-    ))
+    let mut session_manager =
+        ServerSessionManager::new(FILESYSTEM_LAYOUT.get().map(|l| l.session()));
+    session_manager.set_connection_state_listener(|settings, hostname, state| {
+        mqtt::publish_connection_state(hostname, &state);
+        webhook::send_connection_state(settings, hostname, state);
+    });
+
+    RwLock::new(session_manager)
 });
 
 pub fn initialize_environment(layout: afs::Layout) {
@@ -93,7 +113,12 @@ pub enum ServerCoreEvent {
     },
     Buttons(Vec<ButtonEntry>), // Note: this is after mapping
     RequestIDR,
-    CaptureFrame,
+    // The captured frames are saved to "<path_prefix>_input.png" and "<path_prefix>_output.png"
+    CaptureFrame {
+        path_prefix: PathBuf,
+    },
+    // The frame is passed back with ServerCoreContext::send_video_preview_frame()
+    CapturePreviewFrame,
     GameRenderLatencyFeedback(Duration), // only used for SteamVR
     ShutdownPending,
     RestartPending,
@@ -105,15 +130,195 @@ pub struct ConnectionContext {
     bitrate_manager: Mutex<BitrateManager>,
     tracking_manager: RwLock<TrackingManager>,
     decoder_config: Mutex<Option<DecoderInitializationConfig>>,
-    video_mirror_sender: Mutex<Option<broadcast::Sender<Vec<u8>>>>,
-    video_recording_file: Mutex<Option<File>>,
+    // Frames are shared between subscribers instead of being cloned for each of them
+    video_mirror_sender: Mutex<Option<broadcast::Sender<VideoMirrorPacket>>>,
+    // Locked after video_mirror_sender. Lets new mirror subscribers start decoding without
+    // requesting an IDR, which would also affect the headset stream
+    video_mirror_cache: Mutex<Option<VideoMirrorCache>>,
+    video_preview: Arc<VideoPreview>,
+    video_recording_file: Mutex<Option<RecordingFile>>,
+    // Set by StartRecordingWithOptions, the log directory is used otherwise
+    video_recording_dir: Mutex<Option<PathBuf>>,
+    // Used for the {hostname} token of the recording filename template
+    recording_client_hostname: Mutex<Option<String>>,
     connection_threads: Mutex<Vec<JoinHandle<()>>>,
     clients_to_be_removed: Mutex<HashSet<String>>,
     video_channel_sender: Mutex<Option<SyncSender<VideoPacket>>>,
     haptics_sender: Mutex<Option<StreamSender<Haptics>>>,
+    // Shared with ServerCoreContext, for the web server health check
+    lifecycle_state: Arc<RwLock<LifecycleState>>,
+    last_video_frame_time: Mutex<Option<SystemTime>>,
+    // Set by SetAudioState, kept across connections
+    game_audio_muted: AtomicBool,
+    microphone_muted: AtomicBool,
+    // Incremented by SetAudioDevice, to restart the game audio recording with the new device
+    game_audio_device_generation: AtomicU64,
+}
+
+const MAX_VIDEO_MIRROR_CACHE_BYTES: usize = 8 * 1024 * 1024;
+const WEB_SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+static VIDEO_MIRROR_PACKET_INDEX: AtomicU64 = AtomicU64::new(0);
+
+// Size of the header of the framed video mirror protocol: frame index (u64), target timestamp in
+// nanoseconds (u64) and flags (u8, bit 0: IDR, bit 1: decoder config), all little endian
+const VIDEO_MIRROR_HEADER_SIZE: usize = 17;
+
+// The header is sent only to the subscribers that negotiated the framed protocol. The buffer is
+// built once and shared by all subscribers
+#[derive(Clone)]
+struct VideoMirrorPacket {
+    buffer: Bytes,
+}
+
+impl VideoMirrorPacket {
+    fn new(timestamp: Duration, is_idr: bool, is_config: bool, data: &[u8]) -> Self {
+        let mut buffer = BytesMut::with_capacity(VIDEO_MIRROR_HEADER_SIZE + data.len());
+        buffer.put_u64_le(VIDEO_MIRROR_PACKET_INDEX.fetch_add(1, Ordering::Relaxed));
+        buffer.put_u64_le(timestamp.as_nanos() as u64);
+        buffer.put_u8((is_idr as u8) | ((is_config as u8) << 1));
+        buffer.put_slice(data);
+
+        Self {
+            buffer: buffer.freeze(),
+        }
+    }
+
+    fn framed(&self) -> Bytes {
+        self.buffer.clone()
+    }
+
+    fn data(&self) -> Bytes {
+        self.buffer.slice(VIDEO_MIRROR_HEADER_SIZE..)
+    }
+}
+
+// Frames since the last IDR, all needed to decode the next one
+struct VideoMirrorCache {
+    frames: Vec<VideoMirrorPacket>,
+    bytes: usize,
+}
+
+impl VideoMirrorCache {
+    fn new(idr_frame: VideoMirrorPacket) -> Self {
+        Self {
+            bytes: idr_frame.buffer.len(),
+            frames: vec![idr_frame],
+        }
+    }
+
+    // Returns false if the cache grew too big and should be discarded
+    fn push(&mut self, frame: VideoMirrorPacket) -> bool {
+        self.bytes += frame.buffer.len();
+        self.frames.push(frame);
+
+        self.bytes <= MAX_VIDEO_MIRROR_CACHE_BYTES
+    }
+}
+
+struct RecordingFile {
+    file: File,
+    // Path of the first segment, without the extension
+    base_path: PathBuf,
+    extension: &'static str,
+    segment: usize,
+    bytes_written: u64,
+    idr_requested: bool,
+}
+
+impl RecordingFile {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<()> {
+        self.file.write_all(buffer)?;
+        self.bytes_written += buffer.len() as u64;
+
+        Ok(())
+    }
+
+    fn finish(self) {
+        self.file.sync_all().ok();
+    }
+
+    // Every segment starts with the decoder config, so it can be played on its own
+    fn start_next_segment(
+        &mut self,
+        decoder_config: Option<&DecoderInitializationConfig>,
+    ) -> Result<()> {
+        let mut path = self.base_path.clone().into_os_string();
+        path.push(format!(".{}.{}", self.segment + 1, self.extension));
+        let path = PathBuf::from(path);
+
+        self.file = File::create(&path)
+            .with_context(|| format!("Failed to create recording file {}", path.display()))?;
+        self.segment += 1;
+        self.bytes_written = 0;
+        self.idr_requested = false;
+
+        if let Some(config) = decoder_config {
+            self.write(&config.config_buffer)?;
+        }
+
+        Ok(())
+    }
+}
+
+// The directory set with StartRecordingWithOptions takes precedence over the settings
+fn recording_dir(
+    connection_context: &ConnectionContext,
+    capture_config: &CaptureConfig,
+) -> PathBuf {
+    connection_context
+        .video_recording_dir
+        .lock()
+        .clone()
+        .or_else(|| capture_config.recording_dir.clone().map(PathBuf::from))
+        .unwrap_or_else(|| FILESYSTEM_LAYOUT.get().unwrap().log_dir.clone())
 }
 
-pub fn create_recording_file(connection_context: &ConnectionContext, settings: &Settings) {
+// Deletes the oldest recordings that exceed the cleanup limits, except active_path
+fn purge_recordings(
+    dir: &Path,
+    config: &RecordingCleanupConfig,
+    filename_template: &str,
+    active_path: &Path,
+) {
+    let mut recordings = storage::recording_files(dir, filename_template)
+        .into_iter()
+        .filter(|(path, _)| path != active_path)
+        .filter_map(|(path, metadata)| Some((path, metadata.modified().ok()?, metadata.len())))
+        .collect::<Vec<_>>();
+    recordings.sort_by_key(|(_, modified, _)| *modified);
+
+    let max_age = config
+        .max_age_days
+        .map(|days| Duration::from_secs(days.saturating_mul(24 * 60 * 60)));
+    let max_total_size = config
+        .max_total_size_mb
+        .map(|size_mb| size_mb.saturating_mul(1024 * 1024));
+
+    let now = SystemTime::now();
+    let mut total_size = recordings.iter().map(|(_, _, size)| size).sum::<u64>();
+    for (path, modified, size) in recordings {
+        let too_old = max_age
+            .is_some_and(|max_age| now.duration_since(modified).is_ok_and(|age| age > max_age));
+        let too_big = max_total_size.is_some_and(|max_total_size| total_size > max_total_size);
+        if !too_old && !too_big {
+            continue;
+        }
+
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                total_size -= size;
+                info!("Deleted old recording {}", path.display());
+            }
+            Err(e) => warn!("Failed to delete old recording {}: {e}", path.display()),
+        }
+    }
+}
+
+pub fn create_recording_file(
+    connection_context: &ConnectionContext,
+    settings: &Settings,
+) -> Result<()> {
     let codec = settings.video.preferred_codec;
     let ext = match codec {
         CodecType::H264 => "h264",
@@ -121,28 +326,123 @@ pub fn create_recording_file(connection_context: &ConnectionContext, settings: &
         CodecType::AV1 => "av1",
     };
 
-    let path = FILESYSTEM_LAYOUT.get().unwrap().log_dir.join(format!(
-        "recording.{}.{ext}",
-        chrono::Local::now().format("%F.%H-%M-%S")
-    ));
+    let capture_config = &settings.extra.capture;
+
+    let dir = recording_dir(connection_context, capture_config);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create recording directory {}", dir.display()))?;
+    storage::check_dir_writable(&dir)?;
+
+    let template = &capture_config.recording_filename_template;
+    let hostname = connection_context
+        .recording_client_hostname
+        .lock()
+        .clone()
+        .unwrap_or_else(|| "unknown".into());
+    let timestamp = chrono::Local::now()
+        .format(storage::RECORDING_TIMESTAMP_FORMAT)
+        .to_string();
+    let filename = template
+        .replace("{timestamp}", &timestamp)
+        .replace("{hostname}", &hostname);
+    if filename.is_empty() || filename.contains(['/', '\\']) {
+        bail!("Invalid recording filename template \"{template}\"");
+    }
 
-    match File::create(path) {
-        Ok(mut file) => {
-            if let Some(config) = &*connection_context.decoder_config.lock() {
-                file.write_all(&config.config_buffer).ok();
-            }
+    // {index} resolves to the first number that doesn't overwrite an existing recording
+    let mut index = 1;
+    let (base_path, path) = loop {
+        let base_path = dir.join(filename.replace("{index}", &index.to_string()));
+        let mut path = base_path.clone().into_os_string();
+        path.push(format!(".{ext}"));
+        let path = PathBuf::from(path);
 
-            *connection_context.video_recording_file.lock() = Some(file);
+        if !filename.contains("{index}") || !path.exists() {
+            break (base_path, path);
+        }
 
-            connection_context
-                .events_sender
-                .send(ServerCoreEvent::RequestIDR)
-                .ok();
+        index += 1;
+    };
+
+    let file = File::create(&path)
+        .with_context(|| format!("Failed to create recording file {}", path.display()))?;
+
+    // Scanning the directory can be slow, it must not hold up the video thread
+    if let Switch::Enabled(config) = capture_config.recording_cleanup.clone() {
+        let template = template.clone();
+        thread::spawn(move || purge_recordings(&dir, &config, &template, &path));
+    }
+
+    let mut recording = RecordingFile {
+        file,
+        base_path,
+        extension: ext,
+        segment: 1,
+        bytes_written: 0,
+        idr_requested: false,
+    };
+
+    if let Some(config) = &*connection_context.decoder_config.lock() {
+        recording.write(&config.config_buffer)?;
+    }
+
+    *connection_context.video_recording_file.lock() = Some(recording);
+
+    connection_context
+        .events_sender
+        .send(ServerCoreEvent::RequestIDR)
+        .ok();
+
+    Ok(())
+}
+
+// Called by the encoder with the pixels of a frame requested with CaptureFrame. Encoding is slow,
+// so it is done on a separate thread to not stall the rendering
+pub fn save_captured_frame(path: PathBuf, width: u32, height: u32, rgb: Vec<u8>) {
+    thread::spawn(move || {
+        let res = image::RgbImage::from_raw(width, height, rgb)
+            .context("Invalid frame size")
+            .and_then(|image| Ok(image.save_with_format(&path, image::ImageFormat::Png)?));
+
+        match res {
+            Ok(()) => info!("Frame saved to {}", path.display()),
+            Err(e) => error!("Failed to save frame to {}: {e}", path.display()),
         }
-        Err(e) => {
-            error!("Failed to record video on disk: {e}");
+    });
+}
+
+pub fn send_haptics(connection_context: &ConnectionContext, haptics: Haptics) -> Result<()> {
+    let haptics_config = {
+        let session_manager_lock = SESSION_MANAGER.read();
+
+        if session_manager_lock.settings().extra.logging.log_haptics {
+            alvr_events::send_event(EventType::Haptics(HapticsEvent {
+                path: DEVICE_ID_TO_PATH.get(&haptics.device_id).map_or_else(
+                    || format!("Unknown (ID: {:#16x})", haptics.device_id),
+                    |p| (*p).to_owned(),
+                ),
+                duration: haptics.duration,
+                frequency: haptics.frequency,
+                amplitude: haptics.amplitude,
+            }))
         }
-    }
+
+        session_manager_lock
+            .settings()
+            .headset
+            .controllers
+            .as_option()
+            .and_then(|c| c.haptics.as_option().cloned())
+    };
+
+    let Some(config) = haptics_config else {
+        bail!("Controller haptics are disabled");
+    };
+    let Some(sender) = &mut *connection_context.haptics_sender.lock() else {
+        bail!("No client is streaming");
+    };
+
+    sender.send_header(&haptics::map_haptics(&config, haptics))
 }
 
 pub fn notify_restart_driver() {
@@ -157,6 +457,21 @@ pub fn notify_restart_driver() {
     }
 }
 
+// Part of the shutdown that can run from the web server runtime, so it completes even if the host
+// is slow to drop the ServerCoreContext. The rest is done by the drop
+fn prepare_server_exit(connection_context: &ConnectionContext) {
+    *connection_context.lifecycle_state.write() = LifecycleState::ShuttingDown;
+
+    if let Some(recording) = connection_context.video_recording_file.lock().take() {
+        recording.finish();
+    }
+
+    // In-flight requests, including the one that triggered the shutdown, are still completed
+    web_server::notify_shutdown();
+
+    logging_backend::flush();
+}
+
 pub fn settings() -> Settings {
     SESSION_MANAGER.read().settings().clone()
 }
@@ -198,6 +513,11 @@ impl ServerCoreContext {
 
         // Create a temporary StatisticsManager until a headset connects
         let initial_settings = SESSION_MANAGER.read().settings().clone();
+
+        if let Switch::Enabled(config) = &initial_settings.connection.mqtt {
+            mqtt::start(config.clone());
+        }
+
         let stats = StatisticsManager::new(
             initial_settings.connection.statistics_history_size,
             Duration::from_secs_f32(1.0 / 90.0),
@@ -208,7 +528,10 @@ impl ServerCoreContext {
             },
         );
 
+        let lifecycle_state = Arc::new(RwLock::new(LifecycleState::StartingUp));
+
         let connection_context = Arc::new(ConnectionContext {
+            video_preview: Arc::new(VideoPreview::new(events_sender.clone())),
             events_sender,
             statistics_manager: RwLock::new(Some(stats)),
             bitrate_manager: Mutex::new(BitrateManager::new(256, 60.0)),
@@ -217,14 +540,26 @@ impl ServerCoreContext {
             )),
             decoder_config: Mutex::new(None),
             video_mirror_sender: Mutex::new(None),
+            video_mirror_cache: Mutex::new(None),
             video_recording_file: Mutex::new(None),
+            video_recording_dir: Mutex::new(None),
+            recording_client_hostname: Mutex::new(None),
             connection_threads: Mutex::new(Vec::new()),
             clients_to_be_removed: Mutex::new(HashSet::new()),
             video_channel_sender: Mutex::new(None),
             haptics_sender: Mutex::new(None),
+            lifecycle_state: Arc::clone(&lifecycle_state),
+            last_video_frame_time: Mutex::new(None),
+            game_audio_muted: AtomicBool::new(false),
+            microphone_muted: AtomicBool::new(false),
+            game_audio_device_generation: AtomicU64::new(0),
         });
 
-        let webserver_runtime = Runtime::new().unwrap();
+        let mut runtime_builder = runtime::Builder::new_multi_thread();
+        if let Some(threads) = initial_settings.connection.web_server_threads {
+            runtime_builder.worker_threads(threads.max(1));
+        }
+        let webserver_runtime = runtime_builder.enable_all().build().unwrap();
         webserver_runtime.spawn({
             let connection_context = Arc::clone(&connection_context);
             async move { alvr_common::show_err(web_server::web_server(connection_context).await) }
@@ -232,7 +567,7 @@ impl ServerCoreContext {
 
         (
             Self {
-                lifecycle_state: Arc::new(RwLock::new(LifecycleState::StartingUp)),
+                lifecycle_state,
                 is_restarting: RelaxedAtomic::new(false),
                 connection_context,
 
@@ -310,48 +645,28 @@ impl ServerCoreContext {
     pub fn send_haptics(&self, haptics: Haptics) {
         dbg_server_core!("send_haptics");
 
-        let haptics_config = {
-            let session_manager_lock = SESSION_MANAGER.read();
-
-            if session_manager_lock.settings().extra.logging.log_haptics {
-                alvr_events::send_event(EventType::Haptics(HapticsEvent {
-                    path: DEVICE_ID_TO_PATH.get(&haptics.device_id).map_or_else(
-                        || format!("Unknown (ID: {:#16x})", haptics.device_id),
-                        |p| (*p).to_owned(),
-                    ),
-                    duration: haptics.duration,
-                    frequency: haptics.frequency,
-                    amplitude: haptics.amplitude,
-                }))
-            }
-
-            session_manager_lock
-                .settings()
-                .headset
-                .controllers
-                .as_option()
-                .and_then(|c| c.haptics.as_option().cloned())
-        };
-
-        if let (Some(config), Some(sender)) = (
-            haptics_config,
-            &mut *self.connection_context.haptics_sender.lock(),
-        ) {
-            sender
-                .send_header(&haptics::map_haptics(&config, haptics))
-                .ok();
-        }
+        send_haptics(&self.connection_context, haptics).ok();
     }
 
     pub fn set_video_config_nals(&self, config_buffer: Vec<u8>, codec: CodecType) {
         dbg_server_core!("set_video_config_nals");
 
         if let Some(sender) = &*self.connection_context.video_mirror_sender.lock() {
-            sender.send(config_buffer.clone()).ok();
+            sender
+                .send(VideoMirrorPacket::new(
+                    Duration::ZERO,
+                    false,
+                    true,
+                    &config_buffer,
+                ))
+                .ok();
+
+            // The cached frames can't be decoded with the new config
+            *self.connection_context.video_mirror_cache.lock() = None;
         }
 
-        if let Some(file) = &mut *self.connection_context.video_recording_file.lock() {
-            file.write_all(&config_buffer).ok();
+        if let Some(recording) = &mut *self.connection_context.video_recording_file.lock() {
+            recording.write(&config_buffer).ok();
         }
 
         *self.connection_context.decoder_config.lock() = Some(DecoderInitializationConfig {
@@ -360,6 +675,14 @@ impl ServerCoreContext {
         });
     }
 
+    pub fn send_video_preview_frame(&self, width: u32, height: u32, rgb: Vec<u8>) {
+        dbg_server_core!("send_video_preview_frame");
+
+        self.connection_context
+            .video_preview
+            .send_frame(width, height, rgb);
+    }
+
     pub fn send_video_nal(&self, target_timestamp: Duration, nal_buffer: Vec<u8>, is_idr: bool) {
         dbg_server_core!("send_video_nal");
 
@@ -370,6 +693,8 @@ impl ServerCoreContext {
         if let Some(sender) = &*self.connection_context.video_channel_sender.lock() {
             let buffer_size = nal_buffer.len();
 
+            *self.connection_context.last_video_frame_time.lock() = Some(SystemTime::now());
+
             if is_idr {
                 STREAM_CORRUPTED.store(false, Ordering::SeqCst);
             }
@@ -390,10 +715,12 @@ impl ServerCoreContext {
                         .ok();
 
                     if is_idr {
-                        create_recording_file(
+                        if let Err(e) = create_recording_file(
                             &self.connection_context,
                             SESSION_MANAGER.read().settings(),
-                        );
+                        ) {
+                            error!("Failed to record video on disk: {e:?}");
+                        }
                         *LAST_IDR_INSTANT.lock() = Instant::now();
                     }
                 }
@@ -406,12 +733,59 @@ impl ServerCoreContext {
                     .connection
                     .avoid_video_glitching
             {
-                if let Some(sender) = &*self.connection_context.video_mirror_sender.lock() {
-                    sender.send(nal_buffer.clone()).ok();
+                match &*self.connection_context.video_mirror_sender.lock() {
+                    Some(sender) if sender.receiver_count() > 0 => {
+                        let frame =
+                            VideoMirrorPacket::new(target_timestamp, is_idr, false, &nal_buffer);
+                        sender.send(frame.clone()).ok();
+
+                        let mut cache_lock = self.connection_context.video_mirror_cache.lock();
+                        if is_idr {
+                            *cache_lock = Some(VideoMirrorCache::new(frame));
+                        } else if let Some(cache) = &mut *cache_lock {
+                            if !cache.push(frame) {
+                                *cache_lock = None;
+                            }
+                        }
+                    }
+                    // Nobody is watching, the next viewer will get a fresh IDR
+                    _ => *self.connection_context.video_mirror_cache.lock() = None,
                 }
 
-                if let Some(file) = &mut *self.connection_context.video_recording_file.lock() {
-                    file.write_all(&nal_buffer).ok();
+                // Note: settings are read before locking the recording file, to respect the locking
+                // order of create_recording_file()
+                let max_recording_file_size = if let Switch::Enabled(config) = &SESSION_MANAGER
+                    .read()
+                    .settings()
+                    .extra
+                    .capture
+                    .split_video_files
+                {
+                    Some(config.max_file_size_mb * 1024 * 1024)
+                } else {
+                    None
+                };
+
+                if let Some(recording) = &mut *self.connection_context.video_recording_file.lock() {
+                    // Segments are split at IDR frames, so each one can be decoded from the start
+                    if max_recording_file_size.is_some_and(|size| recording.bytes_written >= size) {
+                        if is_idr {
+                            if let Err(e) = recording.start_next_segment(
+                                self.connection_context.decoder_config.lock().as_ref(),
+                            ) {
+                                error!("Failed to split video recording: {e:?}");
+                            }
+                        } else if !recording.idr_requested {
+                            recording.idr_requested = true;
+
+                            self.connection_context
+                                .events_sender
+                                .send(ServerCoreEvent::RequestIDR)
+                                .ok();
+                        }
+                    }
+
+                    recording.write(&nal_buffer).ok();
                 }
 
                 if matches!(
@@ -432,6 +806,9 @@ impl ServerCoreContext {
                     warn!("Dropping video packet. Reason: Can't push to network");
                 }
             } else {
+                // Skipped frames would break the decoding of the cached ones
+                *self.connection_context.video_mirror_cache.lock() = None;
+
                 warn!("Dropping video packet. Reason: Waiting for IDR frame");
             }
 
@@ -522,6 +899,8 @@ impl Drop for ServerCoreContext {
         // Invoke connection runtimes shutdown
         *self.lifecycle_state.write() = LifecycleState::ShuttingDown;
 
+        web_server::shutdown(WEB_SERVER_SHUTDOWN_TIMEOUT);
+
         dbg_server_core!("Setting clients as Disconnecting");
         {
             let mut session_manager_lock = SESSION_MANAGER.write();
@@ -551,6 +930,10 @@ impl Drop for ServerCoreContext {
             thread.join().ok();
         }
 
+        if let Some(recording) = self.connection_context.video_recording_file.lock().take() {
+            recording.finish();
+        }
+
         // apply openvr config for the next launch
         dbg_server_core!("Setting restart settings chache");
         {
@@ -569,6 +952,8 @@ impl Drop for ServerCoreContext {
             thread::sleep(Duration::from_millis(100));
         }
 
+        logging_backend::flush();
+
         // Dropping the webserver runtime is bugged on linux and will prevent StemVR shutdown
         if !cfg!(target_os = "linux") {
             self.webserver_runtime.take();
@@ -0,0 +1,183 @@
+use chrono::{Days, Local, NaiveDate};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+fn archive_path(path: &Path, index: usize) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{index}"));
+
+    path.into()
+}
+
+// Log file that is moved to path.1, path.2, ... when it grows past max_file_size. The oldest
+// archives are deleted to respect max_archives and max_total_size.
+pub struct SizeRotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_file_size: u64,
+    max_archives: usize,
+    max_total_size: u64,
+}
+
+impl SizeRotatingFile {
+    pub fn new(
+        path: PathBuf,
+        append: bool,
+        max_file_size_mb: u64,
+        max_archives: usize,
+        max_total_size_mb: u64,
+    ) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_file_size: u64::max(max_file_size_mb, 1) * BYTES_PER_MB,
+            max_archives,
+            max_total_size: max_total_size_mb * BYTES_PER_MB,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_archives > 0 {
+            fs::remove_file(archive_path(&self.path, self.max_archives)).ok();
+            for index in (1..self.max_archives).rev() {
+                let from = archive_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(from, archive_path(&self.path, index + 1))?;
+                }
+            }
+
+            fs::rename(&self.path, archive_path(&self.path, 1))?;
+        }
+
+        self.file = File::create(&self.path)?;
+        self.size = 0;
+
+        // The current file is included in the total, keep the newest archives that fit
+        let mut total_size = self.max_file_size;
+        for index in 1..=self.max_archives {
+            let path = archive_path(&self.path, index);
+            let Ok(metadata) = fs::metadata(&path) else {
+                break;
+            };
+
+            total_size += metadata.len();
+            if total_size > self.max_total_size {
+                fs::remove_file(path).ok();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for SizeRotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_file_size {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+// Log file named with the current date, a new one is created at local midnight. Files older than
+// retention_days are deleted.
+pub struct DailyRotatingFile {
+    dir: PathBuf,
+    prefix: String,
+    date: NaiveDate,
+    file: File,
+    retention_days: u64,
+}
+
+impl DailyRotatingFile {
+    pub fn new(dir: PathBuf, prefix: &str, retention_days: u64) -> io::Result<Self> {
+        let date = Local::now().date_naive();
+        let file = Self::open(&dir, prefix, date)?;
+
+        let this = Self {
+            dir,
+            prefix: prefix.to_owned(),
+            date,
+            file,
+            retention_days,
+        };
+        this.delete_old_files();
+
+        Ok(this)
+    }
+
+    fn open(dir: &Path, prefix: &str, date: NaiveDate) -> io::Result<File> {
+        fs::create_dir_all(dir)?;
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("{prefix}{}.txt", date.format("%Y-%m-%d"))))
+    }
+
+    fn delete_old_files(&self) {
+        let Some(oldest_date) = self.date.checked_sub_days(Days::new(self.retention_days)) else {
+            return;
+        };
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let maybe_date = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix(&self.prefix))
+                .and_then(|name| name.strip_suffix(".txt"))
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+
+            if maybe_date.is_some_and(|date| date < oldest_date) {
+                fs::remove_file(entry.path()).ok();
+            }
+        }
+    }
+}
+
+impl Write for DailyRotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let today = Local::now().date_naive();
+        if today != self.date {
+            self.file.flush()?;
+            self.file = Self::open(&self.dir, &self.prefix, today)?;
+            self.date = today;
+
+            self.delete_old_files();
+        }
+
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
@@ -1,110 +1,871 @@
-use crate::SESSION_MANAGER;
-use alvr_common::{log::LevelFilter, once_cell::sync::Lazy, LogEntry, LogSeverity};
+use crate::{
+    log_rotation::{DailyRotatingFile, SizeRotatingFile},
+    remote_log::RemoteLogSink,
+    FILESYSTEM_LAYOUT, SESSION_MANAGER,
+};
+use alvr_common::{
+    hash_string, info,
+    log::{self, LevelFilter},
+    once_cell::sync::{Lazy, OnceCell},
+    parking_lot::{Mutex, RwLock},
+    settings_schema::Switch,
+    warn, LogEntry, LogSeverity,
+};
 use alvr_events::{Event, EventType};
-use chrono::Local;
+use alvr_session::{LogFileFormat, DEFAULT_LOG_TIMESTAMP_FORMAT};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, FixedOffset, Local, SecondsFormat, Utc,
+};
 use fern::Dispatch;
-use std::{fs, path::PathBuf};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    env, fs,
+    io::{self, Write},
+    mem,
+    net::IpAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::broadcast;
 
-static CHANNEL_CAPACITY: usize = 256;
-pub static LOGGING_EVENTS_SENDER: Lazy<broadcast::Sender<Event>> =
-    Lazy::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+// Broadcast payload shared by all websocket subscribers. The JSON is serialized lazily by the
+// first subscriber that sends the event and then reused by the others
+pub struct SharedEvent {
+    pub event: Event,
+    json: OnceCell<String>,
+}
 
-pub fn init_logging(session_log_path: Option<PathBuf>, crash_log_path: Option<PathBuf>) {
-    let debug_groups_config = SESSION_MANAGER
+impl SharedEvent {
+    pub fn new(event: Event) -> Arc<Self> {
+        Arc::new(Self {
+            event,
+            json: OnceCell::new(),
+        })
+    }
+
+    pub fn json(&self) -> &str {
+        self.json
+            .get_or_init(|| serde_json::to_string(&self.event).unwrap())
+    }
+}
+
+static EVENT_SEQUENCE: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
+
+const ERROR_EVENTS_CAPACITY: usize = 1024;
+
+// Note: this is forced in init_logging(), to avoid locking the session from within a log call.
+// Carries every event except error logs, which are sent only on ERROR_EVENTS_SENDER. Subscribers
+// interested in error logs must subscribe to both, like /api/events does.
+pub static LOGGING_EVENTS_SENDER: Lazy<broadcast::Sender<Arc<SharedEvent>>> = Lazy::new(|| {
+    let capacity = SESSION_MANAGER
+        .read()
+        .settings()
+        .connection
+        .web_server_events_capacity;
+
+    broadcast::channel(capacity.max(1)).0
+});
+
+// Separate bus for error logs, so they are not dropped together with the other events when a
+// websocket client can't keep up, for example during an error storm
+pub static ERROR_EVENTS_SENDER: Lazy<broadcast::Sender<Arc<SharedEvent>>> =
+    Lazy::new(|| broadcast::channel(ERROR_EVENTS_CAPACITY).0);
+
+// Only carries statistics events, for the /api/statistics endpoint. Also forced in init_logging()
+pub static STATISTICS_EVENTS_SENDER: Lazy<broadcast::Sender<Arc<SharedEvent>>> = Lazy::new(|| {
+    let capacity = SESSION_MANAGER
         .read()
         .settings()
-        .extra
-        .logging
-        .debug_groups
-        .clone();
+        .connection
+        .web_server_statistics_capacity;
+
+    broadcast::channel(capacity.max(1)).0
+});
+
+// Recent log events, to be sent to newly connected dashboards
+static LOG_HISTORY: Lazy<Mutex<VecDeque<Event>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+// Recent non-log events grouped by type, sent to new /api/events subscribers
+static EVENT_REPLAY: Lazy<Mutex<HashMap<&'static str, VecDeque<Event>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Returns the last max_entries log events, in chronological order
+pub fn log_history(max_entries: usize) -> Vec<Event> {
+    let history = LOG_HISTORY.lock();
+
+    history
+        .iter()
+        .skip(history.len().saturating_sub(max_entries))
+        .cloned()
+        .collect()
+}
+
+// Events logged before the first /api/events subscriber connected. None once drained. Log events
+// are not included since GetLogs already returns them
+static STARTUP_EVENTS: Lazy<Mutex<Option<VecDeque<Event>>>> =
+    Lazy::new(|| Mutex::new(Some(VecDeque::new())));
+
+// Returns the buffered events of all types, each type in chronological order. The first caller
+// instead receives all events logged since startup
+pub fn event_replay() -> Vec<Event> {
+    if let Some(startup_events) = STARTUP_EVENTS.lock().take() {
+        return startup_events.into();
+    }
+
+    EVENT_REPLAY
+        .lock()
+        .values()
+        .flat_map(|events| events.iter().cloned())
+        .collect()
+}
+
+// Identical messages closer than this are considered repeated
+const REPEAT_WINDOW: Duration = Duration::from_secs(1);
+// While a message keeps repeating, a summary is emitted at this interval
+const REPEAT_SUMMARY_INTERVAL: Duration = Duration::from_secs(5);
+
+enum Repetition {
+    New { previous_repeats: usize },
+    Suppressed,
+    Summary { repeats: usize },
+}
+
+struct RepeatTracker {
+    hash: u64,
+    severity: LogSeverity,
+    repeats: usize,
+    last_seen: Instant,
+    last_summary: Instant,
+}
+
+impl RepeatTracker {
+    fn new() -> Self {
+        Self {
+            hash: 0,
+            severity: LogSeverity::Info,
+            repeats: 0,
+            last_seen: Instant::now(),
+            last_summary: Instant::now(),
+        }
+    }
+
+    fn check(&mut self, entry: &LogEntry, now: Instant) -> Repetition {
+        let hash = hash_string(&format!("{:?}{}", entry.severity, entry.content));
+
+        if hash == self.hash && now.saturating_duration_since(self.last_seen) < REPEAT_WINDOW {
+            self.last_seen = now;
+            self.repeats += 1;
+
+            if now.saturating_duration_since(self.last_summary) >= REPEAT_SUMMARY_INTERVAL {
+                self.last_summary = now;
+
+                Repetition::Summary {
+                    repeats: mem::take(&mut self.repeats),
+                }
+            } else {
+                Repetition::Suppressed
+            }
+        } else {
+            self.hash = hash;
+            self.severity = entry.severity;
+            self.last_seen = now;
+            self.last_summary = now;
+
+            Repetition::New {
+                previous_repeats: mem::take(&mut self.repeats),
+            }
+        }
+    }
+
+    // Returns the severity and count of the repeats not reported yet. The next message is
+    // considered new even if identical
+    fn flush(&mut self) -> Option<(LogSeverity, usize)> {
+        self.hash = 0;
+
+        (self.repeats > 0).then(|| (self.severity, mem::take(&mut self.repeats)))
+    }
+}
+
+static REPEAT_TRACKER: Lazy<Mutex<RepeatTracker>> = Lazy::new(|| Mutex::new(RepeatTracker::new()));
+
+// Reports suppressed repeats that would otherwise be lost, then flushes all sinks
+pub fn flush() {
+    let pending_repeats = REPEAT_TRACKER.lock().flush();
+    if let Some((severity, repeats)) = pending_repeats {
+        log::log!(
+            severity.into_log_level(),
+            "Previous message repeated {repeats} more times"
+        );
+    }
+
+    log::logger().flush();
+}
+
+struct PendingLog {
+    time: DateTime<FixedOffset>,
+    events: Vec<Event>,
+}
+
+thread_local! {
+    // Set by the top level formatter while the record is passed to the sinks
+    static PENDING_LOG: RefCell<Option<PendingLog>> = const { RefCell::new(None) };
+}
+
+#[derive(Serialize)]
+struct JsonLogLine<'a> {
+    timestamp: String,
+    level: LogSeverity,
+    target: &'a str,
+    thread: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<&'a EventType>,
+}
+
+#[derive(Clone, Copy)]
+enum SinkFormat {
+    File(LogFileFormat),
+    Console { colored: bool },
+}
+
+fn format_event(
+    format: SinkFormat,
+    time: &DateTime<FixedOffset>,
+    event: &Event,
+    record: &log::Record,
+) -> String {
+    match format {
+        SinkFormat::File(LogFileFormat::Text) => format!(
+            "{} [{}] [{}] {}: {}",
+            event.timestamp,
+            event.event_type_string(),
+            alvr_common::current_thread_name(),
+            record.target(),
+            event.message()
+        ),
+        SinkFormat::File(LogFileFormat::JsonLines) => {
+            let (level, event_type) = if let EventType::Log(entry) = &event.event_type {
+                (entry.severity, None)
+            } else {
+                (
+                    LogSeverity::from_log_level(record.level()),
+                    Some(&event.event_type),
+                )
+            };
+
+            serde_json::to_string(&JsonLogLine {
+                timestamp: time.to_rfc3339_opts(SecondsFormat::Millis, false),
+                level,
+                target: record.target(),
+                thread: alvr_common::current_thread_name(),
+                message: event.message(),
+                event: event_type,
+            })
+            .unwrap_or_default()
+        }
+        SinkFormat::Console { colored } => {
+            let level = event.event_type_string();
+            let level = if colored {
+                let color = match &event.event_type {
+                    EventType::Log(entry) => match entry.severity {
+                        LogSeverity::Error => "31",
+                        LogSeverity::Warning => "33",
+                        LogSeverity::Info => "32",
+                        LogSeverity::Debug => "34",
+                    },
+                    _ => "36",
+                };
+
+                format!("\x1b[{color}m{level:<7}\x1b[0m")
+            } else {
+                format!("{level:<7}")
+            };
+
+            format!(
+                "{} {level} [{}] {} {}",
+                time.format("%H:%M:%S"),
+                alvr_common::current_thread_name(),
+                record.target(),
+                event.message()
+            )
+        }
+    }
+}
+
+fn sink_dispatch(format: SinkFormat) -> Dispatch {
+    Dispatch::new().format(move |out, message, record| {
+        PENDING_LOG.with_borrow(|pending| {
+            if let Some(pending) = pending {
+                let lines = pending
+                    .events
+                    .iter()
+                    .map(|event| format_event(format, &pending.time, event, record))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                out.finish(format_args!("{lines}"));
+            } else {
+                out.finish(format_args!("{message}"));
+            }
+        })
+    })
+}
+
+// Level for modules not listed in module_log_levels
+static DEFAULT_LOG_LEVEL: Lazy<RwLock<LevelFilter>> = Lazy::new(|| RwLock::new(LevelFilter::Info));
+// Most verbose level of module_log_levels
+static MAX_MODULE_LOG_LEVEL: Lazy<RwLock<LevelFilter>> =
+    Lazy::new(|| RwLock::new(LevelFilter::Off));
+
+fn apply_log_level(level: LevelFilter) {
+    *DEFAULT_LOG_LEVEL.write() = level;
+    log::set_max_level(level.max(*MAX_MODULE_LOG_LEVEL.read()));
+}
+
+pub fn set_log_level(level: LevelFilter) {
+    // Log with the more verbose of the two levels, so the message is visible when possible
+    if level < *DEFAULT_LOG_LEVEL.read() {
+        info!("Log level set to {level}");
+        apply_log_level(level);
+    } else {
+        apply_log_level(level);
+        info!("Log level set to {level}");
+    }
+}
+
+// Events are logged as JSON by alvr_events::send_event(). Returns None for any other message, even
+// if it looks like JSON.
+fn parse_event_type(message: &str) -> Option<EventType> {
+    if message.starts_with('{') && message.ends_with('}') {
+        serde_json::from_str(message).ok()
+    } else {
+        None
+    }
+}
+
+// Candidates are validated by parsing them, so timestamps and paths like a::b are left untouched.
+// Loopback and unspecified addresses don't identify anyone and are kept for debugging.
+fn redact_ip_addresses(message: &str) -> Cow<'_, str> {
+    static IP_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b|\b[0-9A-Fa-f]*:[0-9A-Fa-f]*:[0-9A-Fa-f:.]*\b")
+            .unwrap()
+    });
+
+    IP_REGEX.replace_all(message, |captures: &Captures| {
+        let candidate = &captures[0];
+        match candidate.parse::<IpAddr>() {
+            Ok(ip) if !ip.is_loopback() && !ip.is_unspecified() => {
+                format!("<ip {:08x}>", hash_string(candidate) as u32)
+            }
+            _ => candidate.to_owned(),
+        }
+    })
+}
+
+// /dev/null might be missing in containers
+fn null_sink(warnings: &mut Vec<String>) -> Box<dyn Write + Send> {
+    match fs::OpenOptions::new().write(true).open("/dev/null") {
+        Ok(file) => Box::new(file),
+        Err(e) => {
+            warnings.push(format!("Failed to open /dev/null, discarding output: {e}"));
+
+            Box::new(io::sink())
+        }
+    }
+}
+
+pub fn init_logging(session_log_path: Option<PathBuf>, crash_log_path: Option<PathBuf>) {
+    let (
+        debug_groups_config,
+        log_history_size,
+        event_replay_size,
+        session_log_rotation,
+        daily_log_files,
+        module_log_levels,
+        append_session_log,
+        session_log_format,
+        timestamp_format,
+        timestamps_utc,
+        redact_ips,
+        remote_log_endpoint,
+        startup_events_capacity,
+    ) = {
+        let session_manager_lock = SESSION_MANAGER.read();
+        let logging_config = &session_manager_lock.settings().extra.logging;
+
+        (
+            logging_config.debug_groups.clone(),
+            logging_config.log_history_size,
+            logging_config.event_replay_size,
+            logging_config.session_log_rotation.clone(),
+            logging_config.daily_log_files.clone(),
+            logging_config
+                .module_log_levels
+                .iter()
+                .map(|(module, severity)| {
+                    (module.clone(), severity.into_log_level().to_level_filter())
+                })
+                .collect::<Vec<_>>(),
+            logging_config.append_session_log,
+            logging_config.session_log_format,
+            logging_config.log_timestamp_format.clone(),
+            logging_config.log_timestamps_utc,
+            logging_config.redact_ip_addresses,
+            logging_config.remote_log_endpoint.clone(),
+            session_manager_lock
+                .settings()
+                .connection
+                .web_server_events_capacity,
+        )
+    };
+    Lazy::force(&LOGGING_EVENTS_SENDER);
+    Lazy::force(&STATISTICS_EVENTS_SENDER);
+
+    // Logged once the logger is ready
+    let mut warnings = vec![];
+
+    // An invalid pattern would make the formatting panic
+    let timestamp_format = if StrftimeItems::new(&timestamp_format).any(|item| item == Item::Error)
+    {
+        warnings.push(format!(
+            "Invalid log timestamp format \"{timestamp_format}\", using the default"
+        ));
+        DEFAULT_LOG_TIMESTAMP_FORMAT.to_owned()
+    } else {
+        timestamp_format
+    };
+
+    *MAX_MODULE_LOG_LEVEL.write() = module_log_levels
+        .iter()
+        .map(|(_, level)| *level)
+        .max()
+        .unwrap_or(LevelFilter::Off);
 
     let mut log_dispatch = Dispatch::new()
         // Note: meta::target() is in the format <crate>::<module>
         .filter({
             let debug_groups_config = debug_groups_config.clone();
             move |meta| {
-                !meta.target().starts_with("mdns_sd")
+                // The longest matching module path wins
+                let level = module_log_levels
+                    .iter()
+                    .filter(|(module, _)| meta.target().starts_with(module.as_str()))
+                    .max_by_key(|(module, _)| module.len())
+                    .map(|(_, level)| *level)
+                    .unwrap_or_else(|| *DEFAULT_LOG_LEVEL.read());
+
+                meta.level() <= level
+                    && !meta.target().starts_with("mdns_sd")
                     && (meta.level() <= LevelFilter::Info
                         || alvr_common::filter_debug_groups(meta.target(), &debug_groups_config))
             }
         })
         .format(move |out, message, record| {
             let maybe_event = format!("{message}");
-            let event_type = if maybe_event.starts_with('{') && maybe_event.ends_with('}') {
-                serde_json::from_str(&maybe_event).unwrap()
+            // Only free-form messages are redacted, structured events are left as is
+            let redact = |message: String| {
+                if redact_ips {
+                    redact_ip_addresses(&message).into_owned()
+                } else {
+                    message
+                }
+            };
+            let mut event_type = if let Some(event_type) = parse_event_type(&maybe_event) {
+                event_type
             } else if record.level() == LevelFilter::Debug
                 && alvr_common::is_enabled_debug_group(record.target(), &debug_groups_config)
             {
                 EventType::DebugGroup {
                     group: record.target().to_string(),
-                    message: message.to_string(),
+                    message: redact(maybe_event),
                 }
             } else {
                 EventType::Log(LogEntry {
                     severity: LogSeverity::from_log_level(record.level()),
-                    content: message.to_string(),
+                    content: redact(maybe_event),
+                    target: record.target().to_string(),
+                    thread: alvr_common::current_thread_name(),
                 })
             };
-            let event = Event {
-                timestamp: Local::now().format("%H:%M:%S.%3f").to_string(),
+            let time = if timestamps_utc {
+                Utc::now().fixed_offset()
+            } else {
+                Local::now().fixed_offset()
+            };
+            let timestamp = time.format(&timestamp_format).to_string();
+
+            // Collapse identical consecutive log messages, to survive error storms
+            let mut repeat_summary = None;
+            if let EventType::Log(entry) = &mut event_type {
+                let mut tracker = REPEAT_TRACKER.lock();
+                match tracker.check(entry, Instant::now()) {
+                    Repetition::New { previous_repeats } if previous_repeats > 0 => {
+                        repeat_summary = Some(Event {
+                            timestamp: timestamp.clone(),
+                            sequence: 0,
+                            event_type: EventType::Log(LogEntry {
+                                severity: tracker.severity,
+                                content: format!(
+                                    "Previous message repeated {previous_repeats} more times"
+                                ),
+                                target: entry.target.clone(),
+                                thread: entry.thread.clone(),
+                            }),
+                        });
+                    }
+                    Repetition::New { .. } => (),
+                    Repetition::Suppressed => return,
+                    Repetition::Summary { repeats } => {
+                        entry.content = format!("{} (repeated {repeats} times)", entry.content);
+                    }
+                }
+            }
+
+            let mut events = Vec::from_iter(repeat_summary);
+            events.push(Event {
+                timestamp,
+                sequence: 0,
                 event_type,
+            });
+
+            // Each sink formats the pending events itself, see sink_dispatch()
+            PENDING_LOG.set(Some(PendingLog { time, events }));
+            out.finish(format_args!("{message}"));
+            let Some(PendingLog { events, .. }) = PENDING_LOG.take() else {
+                return;
             };
-            out.finish(format_args!(
-                "{} [{}] {}",
-                event.timestamp,
-                event.event_type_string(),
-                event.message(),
-            ));
-
-            LOGGING_EVENTS_SENDER.send(event).ok();
+
+            for mut event in events {
+                // Held until the event is broadcast, so sequence numbers follow the send order
+                let mut sequence = EVENT_SEQUENCE.lock();
+                *sequence += 1;
+                event.sequence = *sequence;
+
+                if matches!(event.event_type, EventType::Log(_)) {
+                    let mut history = LOG_HISTORY.lock();
+                    history.push_back(event.clone());
+                    while history.len() > log_history_size {
+                        history.pop_front();
+                    }
+                } else if let Some(startup_events) = &mut *STARTUP_EVENTS.lock() {
+                    startup_events.push_back(event.clone());
+                    while startup_events.len() > startup_events_capacity {
+                        startup_events.pop_front();
+                    }
+                }
+
+                if !matches!(
+                    event.event_type,
+                    EventType::Log(_) | EventType::DebugGroup { .. }
+                ) && event_replay_size > 0
+                {
+                    let mut replay = EVENT_REPLAY.lock();
+                    let type_events = replay.entry(event.event_type.name()).or_default();
+                    type_events.push_back(event.clone());
+                    while type_events.len() > event_replay_size {
+                        type_events.pop_front();
+                    }
+                }
+
+                let event = SharedEvent::new(event);
+
+                if matches!(
+                    event.event.event_type,
+                    EventType::StatisticsSummary(_) | EventType::GraphStatistics(_)
+                ) {
+                    STATISTICS_EVENTS_SENDER.send(Arc::clone(&event)).ok();
+                }
+
+                if matches!(
+                    &event.event.event_type,
+                    EventType::Log(LogEntry {
+                        severity: LogSeverity::Error,
+                        ..
+                    })
+                ) {
+                    ERROR_EVENTS_SENDER.send(event).ok();
+                } else {
+                    LOGGING_EVENTS_SENDER.send(event).ok();
+                }
+            }
         });
 
-    if cfg!(debug_assertions) {
-        log_dispatch = log_dispatch.level(LevelFilter::Debug)
-    } else {
-        log_dispatch = log_dispatch.level(LevelFilter::Info);
-    }
+    // See https://no-color.org
+    let console_format = SinkFormat::Console {
+        colored: !matches!(env::var_os("NO_COLOR"), Some(value) if !value.is_empty()),
+    };
+
+    // The actual levels are checked in the filter, so they can be changed at runtime
+    log_dispatch = log_dispatch.level(LevelFilter::Trace);
 
     log_dispatch = if let Some(path) = session_log_path {
-        log_dispatch.chain(
+        let file_result = if let Switch::Enabled(config) = &session_log_rotation {
+            SizeRotatingFile::new(
+                path.clone(),
+                append_session_log,
+                config.max_file_size_mb,
+                config.max_archives,
+                config.max_total_size_mb,
+            )
+            .map(|file| Box::new(file) as Box<dyn Write + Send>)
+        } else {
             fs::OpenOptions::new()
                 .write(true)
                 .create(true)
-                .truncate(true)
-                .open(path)
-                .unwrap(),
-        )
+                .append(append_session_log)
+                .truncate(!append_session_log)
+                .open(&path)
+                .map(|file| Box::new(file) as Box<dyn Write + Send>)
+        };
+        let mut file = match file_result {
+            Ok(file) => file,
+            Err(e) => {
+                warnings.push(format!(
+                    "Failed to open session log {}: {e}",
+                    path.display()
+                ));
+
+                Box::new(io::sink())
+            }
+        };
+
+        // The separator would break the JSON lines format
+        if append_session_log && session_log_format == LogFileFormat::Text {
+            writeln!(
+                file,
+                "\n========== Session started {} ==========",
+                Local::now().format("%Y-%m-%d %H:%M:%S")
+            )
+            .ok();
+        }
+
+        log_dispatch.chain(sink_dispatch(SinkFormat::File(session_log_format)).chain(file))
     } else if cfg!(target_os = "linux") {
         // this sink is required to make sure all log gets processed and forwarded to the websocket
-        log_dispatch.chain(
-            fs::OpenOptions::new()
-                .write(true)
-                .open("/dev/null")
-                .unwrap(),
-        )
+        log_dispatch.chain(null_sink(&mut warnings))
     } else {
-        log_dispatch.chain(std::io::stdout())
+        // Warnings and errors go to stderr, following the Unix convention
+        log_dispatch
+            .chain(
+                sink_dispatch(console_format)
+                    .filter(|metadata| metadata.level() > log::Level::Warn)
+                    .chain(std::io::stdout()),
+            )
+            .chain(
+                sink_dispatch(console_format)
+                    .level(LevelFilter::Warn)
+                    .chain(std::io::stderr()),
+            )
     };
 
+    if let (Switch::Enabled(config), Some(layout)) = (&daily_log_files, FILESYSTEM_LAYOUT.get()) {
+        match DailyRotatingFile::new(
+            layout.log_dir.clone(),
+            "session_log_",
+            config.retention_days,
+        ) {
+            Ok(file) => {
+                log_dispatch = log_dispatch.chain(
+                    sink_dispatch(SinkFormat::File(config.format))
+                        .chain(Box::new(file) as Box<dyn Write + Send>),
+                )
+            }
+            Err(e) => warnings.push(format!("Failed to create daily log file: {e}")),
+        }
+    }
+
+    if let Switch::Enabled(config) = remote_log_endpoint {
+        match RemoteLogSink::new(config.address, config.protocol) {
+            Ok(sink) => {
+                log_dispatch = log_dispatch.chain(
+                    sink_dispatch(SinkFormat::File(config.format))
+                        .chain(Box::new(sink) as Box<dyn Write + Send>),
+                )
+            }
+            Err(e) => warnings.push(format!("Failed to start remote logging: {e}")),
+        }
+    }
+
     log_dispatch = if let Some(path) = crash_log_path {
+        let crash_log: Box<dyn Write + Send> = match fern::log_file(&path) {
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                warnings.push(format!("Failed to open crash log {}: {e}", path.display()));
+
+                Box::new(io::sink())
+            }
+        };
+
         log_dispatch.chain(
-            Dispatch::new()
+            sink_dispatch(SinkFormat::File(LogFileFormat::Text))
                 .level(LevelFilter::Error)
-                .chain(fern::log_file(path).unwrap()),
+                .chain(crash_log),
         )
     } else if cfg!(target_os = "linux") {
+        log_dispatch.chain(null_sink(&mut warnings))
+    } else {
         log_dispatch.chain(
-            fs::OpenOptions::new()
-                .write(true)
-                .open("/dev/null")
-                .unwrap(),
+            sink_dispatch(console_format)
+                .level(LevelFilter::Error)
+                .chain(std::io::stderr()),
         )
-    } else {
-        log_dispatch.chain(std::io::stderr())
     };
 
     log_dispatch.apply().unwrap();
 
+    if cfg!(debug_assertions) {
+        apply_log_level(LevelFilter::Debug);
+    } else {
+        apply_log_level(LevelFilter::Info);
+    }
+
+    for warning in warnings {
+        warn!("{warning}");
+    }
+
     alvr_common::set_panic_hook();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_type() {
+        assert!(parse_event_type("{not json}").is_none());
+        assert!(parse_event_type("plain message").is_none());
+
+        let event_json = serde_json::to_string(&EventType::DriversList(vec![])).unwrap();
+        assert!(matches!(
+            parse_event_type(&event_json),
+            Some(EventType::DriversList(_))
+        ));
+    }
+
+    #[test]
+    fn test_redact_ip_addresses() {
+        let redacted =
+            redact_ip_addresses("Client 192.168.1.20:9943 and fe80::1c2b:3ff:fe4a:1 joined");
+        assert!(!redacted.contains("192.168.1.20"));
+        assert!(!redacted.contains("fe80::1c2b"));
+        assert!(redacted.contains(":9943 and"));
+
+        // Same address, same hash
+        assert_eq!(
+            redact_ip_addresses("10.0.0.2"),
+            redact_ip_addresses("10.0.0.2")
+        );
+
+        for message in [
+            "Listening on 127.0.0.1 and 0.0.0.0",
+            "alvr_server_core::connection at 12:00:00.123",
+            "Version 999.1.2.3",
+        ] {
+            assert_eq!(redact_ip_addresses(message), message);
+        }
+    }
+
+    #[test]
+    fn test_json_lines_format() {
+        let event = Event {
+            timestamp: "12:00:00.000".into(),
+            sequence: 1,
+            event_type: EventType::Log(LogEntry {
+                severity: LogSeverity::Warning,
+                content: "message".into(),
+                target: "alvr_server_core::connection".into(),
+                thread: "connection".into(),
+            }),
+        };
+        let record = log::Record::builder()
+            .level(log::Level::Warn)
+            .target("alvr_server_core::connection")
+            .build();
+
+        let line = format_event(
+            SinkFormat::File(LogFileFormat::JsonLines),
+            &Local::now().fixed_offset(),
+            &event,
+            &record,
+        );
+        let value = serde_json::from_str::<serde_json::Value>(&line).unwrap();
+        assert_eq!(value["level"], "Warning");
+        assert_eq!(value["target"], "alvr_server_core::connection");
+        assert!(value["thread"].is_string());
+        assert_eq!(value["message"], "message");
+        assert!(value.get("event").is_none());
+    }
+
+    #[test]
+    fn test_repeat_tracker() {
+        let entry = LogEntry {
+            severity: LogSeverity::Error,
+            content: "failure".into(),
+            target: String::new(),
+            thread: String::new(),
+        };
+        let mut tracker = RepeatTracker::new();
+        let start = Instant::now();
+
+        assert!(matches!(
+            tracker.check(&entry, start),
+            Repetition::New {
+                previous_repeats: 0
+            }
+        ));
+        assert!(matches!(
+            tracker.check(&entry, start + Duration::from_millis(500)),
+            Repetition::Suppressed
+        ));
+        assert!(matches!(
+            tracker.check(&entry, start + Duration::from_millis(900)),
+            Repetition::Suppressed
+        ));
+
+        let other = LogEntry {
+            severity: LogSeverity::Error,
+            content: "other failure".into(),
+            target: String::new(),
+            thread: String::new(),
+        };
+        assert!(matches!(
+            tracker.check(&other, start + Duration::from_secs(1)),
+            Repetition::New {
+                previous_repeats: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn test_repeat_tracker_flush() {
+        let entry = LogEntry {
+            severity: LogSeverity::Warning,
+            content: "failure".into(),
+            target: String::new(),
+            thread: String::new(),
+        };
+        let mut tracker = RepeatTracker::new();
+        let start = Instant::now();
+
+        tracker.check(&entry, start);
+        tracker.check(&entry, start + Duration::from_millis(100));
+        tracker.check(&entry, start + Duration::from_millis(200));
+
+        assert!(matches!(tracker.flush(), Some((LogSeverity::Warning, 2))));
+        assert!(tracker.flush().is_none());
+
+        // The repeats were already reported, so the same message is not suppressed anymore
+        assert!(matches!(
+            tracker.check(&entry, start + Duration::from_millis(300)),
+            Repetition::New {
+                previous_repeats: 0
+            }
+        ));
+    }
+}
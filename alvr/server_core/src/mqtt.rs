@@ -0,0 +1,113 @@
+use crate::logging_backend::LOGGING_EVENTS_SENDER;
+use alvr_common::{info, once_cell::sync::OnceCell, warn, ConnectionState};
+use alvr_events::EventType;
+use alvr_session::MqttConfig;
+use rumqttc::{Client, MqttOptions, QoS};
+use std::{thread, time::Duration};
+use tokio::sync::broadcast::error::RecvError;
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const REQUEST_QUEUE_CAPACITY: usize = 64;
+
+struct MqttPublisher {
+    client: Client,
+    config: MqttConfig,
+}
+
+impl MqttPublisher {
+    fn publish(&self, subtopic: &str, retain: bool, payload: String) {
+        let topic = format!("{}/{subtopic}", self.config.topic_prefix);
+
+        // Messages are dropped while the broker is unreachable
+        self.client
+            .try_publish(topic, QoS::AtMostOnce, retain, payload)
+            .ok();
+    }
+}
+
+static PUBLISHER: OnceCell<MqttPublisher> = OnceCell::new();
+
+pub fn start(config: MqttConfig) {
+    let mut options = MqttOptions::new(
+        config.client_id.clone(),
+        config.broker_host.clone(),
+        config.broker_port,
+    );
+    options.set_keep_alive(KEEP_ALIVE_INTERVAL);
+    if let Some(credentials) = &config.credentials {
+        options.set_credentials(credentials.username.clone(), credentials.password.clone());
+    }
+
+    let (client, mut connection) = Client::new(options, REQUEST_QUEUE_CAPACITY);
+    if PUBLISHER.set(MqttPublisher { client, config }).is_err() {
+        // Already started by a previous server instance in this process
+        return;
+    }
+
+    thread::spawn(move || {
+        let mut connected = false;
+        for notification in connection.iter() {
+            match notification {
+                Ok(_) => {
+                    if !connected {
+                        info!("Connected to MQTT broker");
+                        connected = true;
+                    }
+                }
+                Err(e) => {
+                    if connected {
+                        warn!("MQTT connection lost: {e}");
+                        connected = false;
+                    }
+
+                    thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        }
+    });
+
+    thread::spawn(|| {
+        let publisher = PUBLISHER.get().unwrap();
+        // Error logs are not published, so ERROR_EVENTS_SENDER is not needed
+        let mut events_receiver = LOGGING_EVENTS_SENDER.subscribe();
+        let mut last_battery = None;
+
+        loop {
+            let event = match events_receiver.blocking_recv() {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+
+            if let EventType::StatisticsSummary(summary) = &event.event.event_type {
+                if publisher.config.publish_statistics {
+                    publisher.publish("statistics", false, serde_json::to_string(summary).unwrap());
+                }
+
+                let battery = (summary.battery_hmd, summary.hmd_plugged);
+                if publisher.config.publish_battery && last_battery != Some(battery) {
+                    publisher.publish(
+                        "battery/hmd",
+                        true,
+                        serde_json::json!({ "level": battery.0, "plugged": battery.1 }).to_string(),
+                    );
+                    last_battery = Some(battery);
+                }
+            }
+        }
+    });
+}
+
+// Called with the session lock held
+pub fn publish_connection_state(hostname: &str, state: &ConnectionState) {
+    if let Some(publisher) = PUBLISHER.get() {
+        if publisher.config.publish_connection_state {
+            publisher.publish(
+                &format!("clients/{hostname}/connection_state"),
+                true,
+                format!("{state:?}"),
+            );
+        }
+    }
+}
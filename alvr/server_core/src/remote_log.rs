@@ -0,0 +1,138 @@
+use alvr_session::SocketProtocol;
+use std::{
+    io::{self, Write},
+    mem,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const QUEUE_CAPACITY: usize = 4096;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+pub static DROPPED_REMOTE_LOG_LINES: AtomicU64 = AtomicU64::new(0);
+
+// Log sink that sends each line to a remote collector from a background thread. Writing never
+// blocks: lines are dropped if the queue is full or the collector is unreachable.
+pub struct RemoteLogSink {
+    sender: SyncSender<Vec<u8>>,
+    line: Vec<u8>,
+}
+
+impl RemoteLogSink {
+    pub fn new(address: String, protocol: SocketProtocol) -> io::Result<Self> {
+        if !address
+            .rsplit_once(':')
+            .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid remote log address \"{address}\", expected host:port"),
+            ));
+        }
+
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+
+        thread::Builder::new()
+            .name("remote_log".into())
+            .spawn(move || match protocol {
+                SocketProtocol::Udp => udp_loop(&address, receiver),
+                SocketProtocol::Tcp => tcp_loop(&address, receiver),
+            })?;
+
+        Ok(Self {
+            sender,
+            line: vec![],
+        })
+    }
+}
+
+impl Write for RemoteLogSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A line can be split into multiple writes, but each line must be sent whole to get one
+        // UDP datagram per line
+        for chunk in buf.split_inclusive(|byte| *byte == b'\n') {
+            self.line.extend_from_slice(chunk);
+
+            if chunk.ends_with(b"\n") && self.sender.try_send(mem::take(&mut self.line)).is_err() {
+                count_dropped_line();
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn resolve(address: &str) -> Option<SocketAddr> {
+    address.to_socket_addrs().ok()?.next()
+}
+
+fn count_dropped_line() {
+    DROPPED_REMOTE_LOG_LINES.fetch_add(1, Ordering::Relaxed);
+}
+
+// Note: errors are not logged by the sender threads, it would feed back into the sink
+
+fn udp_loop(address: &str, receiver: Receiver<Vec<u8>>) {
+    let mut target = None;
+    let mut socket = None;
+    for line in receiver {
+        if target.is_none() {
+            target = resolve(address);
+        }
+        let Some(target) = target else {
+            count_dropped_line();
+            continue;
+        };
+
+        if socket.is_none() {
+            socket = if target.is_ipv4() {
+                UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()
+            } else {
+                UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0)).ok()
+            };
+        }
+
+        if socket
+            .as_ref()
+            .is_none_or(|socket| socket.send_to(&line, target).is_err())
+        {
+            count_dropped_line();
+        }
+    }
+}
+
+fn tcp_loop(address: &str, receiver: Receiver<Vec<u8>>) {
+    let mut stream = None::<TcpStream>;
+    let mut reconnect_deadline = Instant::now();
+    for line in receiver {
+        if stream.is_none() && Instant::now() >= reconnect_deadline {
+            stream = resolve(address)
+                .and_then(|address| TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).ok())
+                .filter(|stream| stream.set_write_timeout(Some(CONNECT_TIMEOUT)).is_ok());
+
+            reconnect_deadline = Instant::now() + RECONNECT_INTERVAL;
+        }
+
+        if let Some(connected_stream) = &mut stream {
+            if connected_stream.write_all(&line).is_err() {
+                stream = None;
+                count_dropped_line();
+
+                // The collector might have been restarted, retry with the next line
+                reconnect_deadline = Instant::now();
+            }
+        } else {
+            count_dropped_line();
+        }
+    }
+}
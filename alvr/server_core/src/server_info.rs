@@ -0,0 +1,247 @@
+use alvr_common::{anyhow::Result, debug, once_cell::sync::Lazy};
+use alvr_session::CodecType;
+use ash::vk;
+use serde_json as json;
+use std::ffi::CStr;
+use sysinfo::System;
+
+static SERVER_INFO: Lazy<String> = Lazy::new(|| {
+    let gpus = GPUS
+        .iter()
+        .map(|gpu| {
+            json::json!({
+                "vendor": vendor_name(gpu.vendor_id),
+                "model": gpu.model,
+                "driver_name": gpu.driver_name,
+                "driver_version": gpu.driver_version,
+                "vulkan_version": gpu.vulkan_version,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json::json!({
+        "os": os_info(),
+        "cpu": cpu_info(),
+        "gpus": gpus,
+        "alvr": {
+            "version": alvr_common::ALVR_VERSION.to_string(),
+            "git_commit": option_env!("ALVR_GIT_COMMIT"),
+            "protocol_id": alvr_common::protocol_id(),
+            "debug_build": cfg!(debug_assertions),
+            "target_os": std::env::consts::OS,
+            "target_arch": std::env::consts::ARCH,
+        },
+    })
+    .to_string()
+});
+
+pub fn server_info_json() -> &'static str {
+    &SERVER_INFO
+}
+
+fn os_info() -> json::Value {
+    json::json!({
+        "name": System::name(),
+        "version": System::os_version(),
+        "long_version": System::long_os_version(),
+        "kernel_version": System::kernel_version(),
+    })
+}
+
+fn cpu_info() -> json::Value {
+    let mut system = System::new();
+    system.refresh_cpu_all();
+    system.refresh_memory();
+
+    json::json!({
+        "brand": system.cpus().first().map(|cpu| cpu.brand().trim().to_owned()),
+        "threads": system.cpus().len(),
+        "memory_mb": system.total_memory() / 1024 / 1024,
+    })
+}
+
+fn vendor_name(vendor_id: u32) -> &'static str {
+    match vendor_id {
+        0x10de => "NVIDIA",
+        0x1002 => "AMD",
+        0x8086 => "Intel",
+        0x13b5 => "ARM",
+        0x5143 => "Qualcomm",
+        _ => "Unknown",
+    }
+}
+
+// Used only if the driver doesn't report VkPhysicalDeviceDriverProperties
+fn decode_driver_version(vendor_id: u32, version: u32) -> String {
+    if vendor_id == 0x10de {
+        format!(
+            "{}.{}.{}.{}",
+            version >> 22,
+            (version >> 14) & 0xff,
+            (version >> 6) & 0xff,
+            version & 0x3f
+        )
+    } else {
+        format!(
+            "{}.{}.{}",
+            vk::api_version_major(version),
+            vk::api_version_minor(version),
+            vk::api_version_patch(version)
+        )
+    }
+}
+
+fn c_str_to_string(c_str: Result<&CStr, std::ffi::FromBytesUntilNulError>) -> String {
+    c_str
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+struct GpuInfo {
+    vendor_id: u32,
+    model: String,
+    driver_name: String,
+    driver_version: String,
+    vulkan_version: String,
+    // From the Vulkan video encode extensions, like VK_KHR_video_encode_h264
+    video_encode_codecs: Vec<CodecType>,
+}
+
+// Creating a Vulkan instance is slow, so the GPUs are probed only once
+static GPUS: Lazy<Vec<GpuInfo>> = Lazy::new(|| match probe_gpus() {
+    Ok(gpus) => gpus,
+    Err(e) => {
+        debug!("Failed to query GPU info: {e}");
+        vec![]
+    }
+});
+
+fn probe_gpus() -> Result<Vec<GpuInfo>> {
+    let entry = unsafe { ash::Entry::load()? };
+
+    let instance_version =
+        unsafe { entry.try_enumerate_instance_version()? }.unwrap_or(vk::API_VERSION_1_0);
+    let app_info =
+        vk::ApplicationInfo::default().api_version(instance_version.min(vk::API_VERSION_1_2));
+    let instance = unsafe {
+        entry.create_instance(
+            &vk::InstanceCreateInfo::default().application_info(&app_info),
+            None,
+        )?
+    };
+
+    let devices = unsafe { instance.enumerate_physical_devices() };
+
+    let gpus = devices.map(|devices| {
+        devices
+            .into_iter()
+            .map(|device| {
+                let properties = unsafe { instance.get_physical_device_properties(device) };
+
+                let mut driver_name = String::new();
+                let mut driver_info = String::new();
+                if instance_version >= vk::API_VERSION_1_2
+                    && properties.api_version >= vk::API_VERSION_1_2
+                {
+                    let mut driver_properties = vk::PhysicalDeviceDriverProperties::default();
+                    let mut properties2 =
+                        vk::PhysicalDeviceProperties2::default().push_next(&mut driver_properties);
+                    unsafe { instance.get_physical_device_properties2(device, &mut properties2) };
+
+                    driver_name = c_str_to_string(driver_properties.driver_name_as_c_str());
+                    driver_info = c_str_to_string(driver_properties.driver_info_as_c_str());
+                }
+
+                let driver_version = if driver_info.is_empty() {
+                    decode_driver_version(properties.vendor_id, properties.driver_version)
+                } else {
+                    driver_info
+                };
+
+                let extensions = unsafe { instance.enumerate_device_extension_properties(device) }
+                    .unwrap_or_default();
+                let video_encode_codecs = [
+                    ("VK_KHR_video_encode_h264", CodecType::H264),
+                    ("VK_KHR_video_encode_h265", CodecType::Hevc),
+                    ("VK_KHR_video_encode_av1", CodecType::AV1),
+                ]
+                .into_iter()
+                .filter(|(name, _)| {
+                    extensions.iter().any(|extension| {
+                        c_str_to_string(extension.extension_name_as_c_str()) == *name
+                    })
+                })
+                .map(|(_, codec)| codec)
+                .collect();
+
+                GpuInfo {
+                    vendor_id: properties.vendor_id,
+                    model: c_str_to_string(properties.device_name_as_c_str()),
+                    driver_name,
+                    driver_version,
+                    vulkan_version: format!(
+                        "{}.{}.{}",
+                        vk::api_version_major(properties.api_version),
+                        vk::api_version_minor(properties.api_version),
+                        vk::api_version_patch(properties.api_version)
+                    ),
+                    video_encode_codecs,
+                }
+            })
+            .collect()
+    });
+
+    unsafe { instance.destroy_instance(None) };
+
+    Ok(gpus?)
+}
+
+fn hardware_encoder_name(vendor_id: u32) -> Option<&'static str> {
+    match vendor_id {
+        0x10de => Some("NVENC"),
+        0x1002 if cfg!(windows) => Some("AMF"),
+        0x1002 | 0x8086 if cfg!(target_os = "linux") => Some("VA-API"),
+        0x8086 => Some("Quick Sync"),
+        _ => None,
+    }
+}
+
+static AVAILABLE_ENCODERS: Lazy<String> = Lazy::new(|| {
+    let mut encoders = GPUS
+        .iter()
+        .filter_map(|gpu| {
+            let name = hardware_encoder_name(gpu.vendor_id)?;
+
+            // Not all drivers expose the Vulkan video extensions, in that case the codecs
+            // supported by all the GPUs of the vendor are reported
+            let (codecs, codecs_verified) = if gpu.video_encode_codecs.is_empty() {
+                (vec![CodecType::H264, CodecType::Hevc], false)
+            } else {
+                (gpu.video_encode_codecs.clone(), true)
+            };
+
+            Some(json::json!({
+                "name": name,
+                "gpu": gpu.model,
+                "hardware_accelerated": true,
+                "codecs": codecs,
+                "codecs_verified": codecs_verified,
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    // Used as a fallback if no hardware encoder works
+    encoders.push(json::json!({
+        "name": "x264",
+        "gpu": null,
+        "hardware_accelerated": false,
+        "codecs": [CodecType::H264],
+        "codecs_verified": true,
+    }));
+
+    json::Value::from(encoders).to_string()
+});
+
+pub fn available_encoders_json() -> &'static str {
+    &AVAILABLE_ENCODERS
+}
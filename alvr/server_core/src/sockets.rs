@@ -1,10 +1,86 @@
 use alvr_common::{
     anyhow::{bail, Result},
+    once_cell::sync::Lazy,
+    parking_lot::Mutex,
     warn, ToAny,
 };
 use flume::TryRecvError;
 use mdns_sd::{Receiver, ServiceDaemon, ServiceEvent};
-use std::{collections::HashMap, net::IpAddr};
+use serde::Serialize;
+use serde_json as json;
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+};
+use sysinfo::Networks;
+
+const MAX_RECENT_ANNOUNCEMENTS: usize = 20;
+
+#[derive(Serialize, Clone)]
+struct ClientAnnouncement {
+    hostname: String,
+    address: IpAddr,
+    protocol_id: String,
+    compatible: bool,
+    timestamp: String,
+}
+
+#[derive(Default)]
+struct DiscoveryStatus {
+    running: bool,
+    error: Option<String>,
+    recent_announcements: VecDeque<ClientAnnouncement>,
+}
+
+static DISCOVERY_STATUS: Lazy<Mutex<DiscoveryStatus>> =
+    Lazy::new(|| Mutex::new(DiscoveryStatus::default()));
+
+// Used to diagnose clients that can't find the server
+pub fn discovery_status_json() -> String {
+    let networks = Networks::new_with_refreshed_list();
+    let mut interfaces = networks
+        .iter()
+        .map(|(name, data)| {
+            let addresses = data
+                .ip_networks()
+                .iter()
+                .map(|network| network.addr)
+                .collect::<Vec<_>>();
+
+            (name.clone(), addresses)
+        })
+        .filter(|(_, addresses)| !addresses.is_empty())
+        .collect::<Vec<_>>();
+    interfaces.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    // Loopback and link-local addresses can't be reached by the headset
+    let mut local_ips = interfaces
+        .iter()
+        .flat_map(|(_, addresses)| addresses)
+        .filter(|ip| match ip {
+            IpAddr::V4(ip) => !ip.is_loopback() && !ip.is_link_local(),
+            IpAddr::V6(ip) => !ip.is_loopback() && (ip.segments()[0] & 0xffc0) != 0xfe80,
+        })
+        .copied()
+        .collect::<Vec<_>>();
+    local_ips.sort();
+    local_ips.dedup();
+
+    let status = DISCOVERY_STATUS.lock();
+
+    json::json!({
+        "running": status.running,
+        "error": status.error,
+        "service_type": alvr_sockets::MDNS_SERVICE_TYPE,
+        "interfaces": interfaces
+            .into_iter()
+            .map(|(name, addresses)| json::json!({ "name": name, "addresses": addresses }))
+            .collect::<Vec<_>>(),
+        "local_ips": local_ips,
+        "recent_announcements": status.recent_announcements,
+    })
+    .to_string()
+}
 
 pub struct WelcomeSocket {
     mdns_receiver: Receiver<ServiceEvent>,
@@ -12,9 +88,16 @@ pub struct WelcomeSocket {
 
 impl WelcomeSocket {
     pub fn new() -> Result<Self> {
-        let mdns_receiver = ServiceDaemon::new()?.browse(alvr_sockets::MDNS_SERVICE_TYPE)?;
+        let res =
+            ServiceDaemon::new().and_then(|daemon| daemon.browse(alvr_sockets::MDNS_SERVICE_TYPE));
 
-        Ok(Self { mdns_receiver })
+        let mut status = DISCOVERY_STATUS.lock();
+        status.running = res.is_ok();
+        status.error = res.as_ref().err().map(|e| e.to_string());
+
+        Ok(Self {
+            mdns_receiver: res?,
+        })
     }
 
     // Returns: client IP, client hostname
@@ -53,6 +136,18 @@ impl WelcomeSocket {
                             warn!("Found incompatible client {hostname}! {reason}\n{protocols}");
                         }
 
+                        let mut status = DISCOVERY_STATUS.lock();
+                        status.recent_announcements.push_back(ClientAnnouncement {
+                            hostname: hostname.to_owned(),
+                            address,
+                            protocol_id: client_protocol.to_owned(),
+                            compatible: client_protocol == server_protocol,
+                            timestamp: chrono::Local::now().to_rfc3339(),
+                        });
+                        while status.recent_announcements.len() > MAX_RECENT_ANNOUNCEMENTS {
+                            status.recent_announcements.pop_front();
+                        }
+
                         clients.insert(hostname.into(), address);
                     }
                 }
@@ -64,3 +159,9 @@ impl WelcomeSocket {
         Ok(clients)
     }
 }
+
+impl Drop for WelcomeSocket {
+    fn drop(&mut self) {
+        DISCOVERY_STATUS.lock().running = false;
+    }
+}
@@ -34,6 +34,20 @@ impl Default for HistoryFrame {
     }
 }
 
+#[derive(Clone, Copy)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+}
+
+// Entry exposed by the /api/metrics endpoint
+pub struct Metric {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+    pub value: f64,
+}
+
 #[derive(Default, Clone)]
 struct BatteryData {
     gauge_value: f32,
@@ -58,6 +72,9 @@ pub struct StatisticsManager {
     last_vsync_time: Instant,
     frame_interval: Duration,
     last_throughput_directives: BitrateDirectives,
+    last_bitrate_bps: f32,
+    last_network_latency: Duration,
+    last_summary: Option<StatisticsSummary>,
 }
 
 impl StatisticsManager {
@@ -90,6 +107,9 @@ impl StatisticsManager {
             last_vsync_time: Instant::now(),
             frame_interval: nominal_server_frame_interval,
             last_throughput_directives: BitrateDirectives::default(),
+            last_bitrate_bps: 0.0,
+            last_network_latency: Duration::ZERO,
+            last_summary: None,
         }
     }
 
@@ -232,7 +252,7 @@ impl StatisticsManager {
 
                 let interval_secs = FULL_REPORT_INTERVAL.as_secs_f32();
 
-                alvr_events::send_event(EventType::StatisticsSummary(StatisticsSummary {
+                let summary = StatisticsSummary {
                     video_packets_total: self.video_packets_total,
                     video_packets_per_sec: (self.video_packets_partial_sum as f32 / interval_secs)
                         as _,
@@ -262,7 +282,9 @@ impl StatisticsManager {
                         .cloned()
                         .unwrap_or_default()
                         .is_plugged,
-                }));
+                };
+                self.last_summary = Some(summary.clone());
+                alvr_events::send_event(EventType::StatisticsSummary(summary));
 
                 self.video_packets_partial_sum = 0;
                 self.video_bytes_partial_sum = 0;
@@ -275,6 +297,9 @@ impl StatisticsManager {
             let bitrate_bps = packet_bits
                 / Duration::max(self.last_frame_present_interval, EPS_INTERVAL).as_secs_f32();
 
+            self.last_bitrate_bps = bitrate_bps;
+            self.last_network_latency = network_latency;
+
             // todo: use target timestamp in nanoseconds. the dashboard needs to use the first
             // timestamp as the graph time origin.
             alvr_events::send_event(EventType::GraphStatistics(GraphStatistics {
@@ -304,6 +329,52 @@ impl StatisticsManager {
         self.motion_to_photon_latency_average.get_average()
     }
 
+    // Last summary sent to the dashboard, None if no statistics were reported yet
+    pub fn last_summary(&self) -> Option<&StatisticsSummary> {
+        self.last_summary.as_ref()
+    }
+
+    pub fn metrics(&self) -> Vec<Metric> {
+        vec![
+            Metric {
+                name: "alvr_video_bytes_total",
+                help: "Total video bytes sent",
+                kind: MetricKind::Counter,
+                value: self.video_bytes_total as f64,
+            },
+            Metric {
+                name: "alvr_video_packets_total",
+                help: "Total video packets sent",
+                kind: MetricKind::Counter,
+                value: self.video_packets_total as f64,
+            },
+            Metric {
+                name: "alvr_packets_lost_total",
+                help: "Total video packets lost",
+                kind: MetricKind::Counter,
+                value: self.packets_lost_total as f64,
+            },
+            Metric {
+                name: "alvr_bitrate_bps",
+                help: "Current video bitrate in bits per second",
+                kind: MetricKind::Gauge,
+                value: self.last_bitrate_bps as f64,
+            },
+            Metric {
+                name: "alvr_motion_to_photon_latency_seconds",
+                help: "Average motion-to-photon latency",
+                kind: MetricKind::Gauge,
+                value: self.motion_to_photon_latency_average().as_secs_f64(),
+            },
+            Metric {
+                name: "alvr_network_latency_seconds",
+                help: "Last estimated network latency",
+                kind: MetricKind::Gauge,
+                value: self.last_network_latency.as_secs_f64(),
+            },
+        ]
+    }
+
     pub fn tracker_pose_time_offset(&self) -> Duration {
         // This is the opposite of the client's StatisticsManager::tracker_prediction_offset().
         self.steamvr_pipeline_latency
@@ -0,0 +1,173 @@
+use crate::{ConnectionContext, FILESYSTEM_LAYOUT, SESSION_MANAGER};
+use alvr_common::anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use serde_json as json;
+use std::{
+    fs::{self, File},
+    path::{self, Path, PathBuf},
+};
+use sysinfo::Disks;
+
+const RECORDING_EXTENSIONS: [&str; 3] = ["h264", "h265", "av1"];
+
+pub const RECORDING_TIMESTAMP_FORMAT: &str = "%F.%H-%M-%S";
+const RECORDING_TIMESTAMP_LEN: usize = "2000-01-01.00-00-00".len();
+
+// Whether the name could have been produced by the filename template. Tokens match only what they
+// can resolve to, so that a template such as "{timestamp}" doesn't match arbitrary names
+fn matches_filename_template(name: &str, template: &str) -> bool {
+    if let Some(template) = template.strip_prefix("{timestamp}") {
+        name.get(..RECORDING_TIMESTAMP_LEN)
+            .is_some_and(|timestamp| {
+                NaiveDateTime::parse_from_str(timestamp, RECORDING_TIMESTAMP_FORMAT).is_ok()
+            })
+            && matches_filename_template(&name[RECORDING_TIMESTAMP_LEN..], template)
+    } else if let Some(template) = template.strip_prefix("{index}") {
+        let digits_count = name.len() - name.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+        (1..=digits_count).any(|len| matches_filename_template(&name[len..], template))
+    } else if let Some(template) = template.strip_prefix("{hostname}") {
+        name.char_indices()
+            .any(|(idx, c)| matches_filename_template(&name[idx + c.len_utf8()..], template))
+    } else {
+        match (template.chars().next(), name.chars().next()) {
+            (None, None) => true,
+            (Some(template_char), Some(name_char)) if template_char == name_char => {
+                let len = name_char.len_utf8();
+                matches_filename_template(&name[len..], &template[len..])
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_recording_file_name(file_name: &str, template: &str) -> bool {
+    let Some(name) = RECORDING_EXTENSIONS
+        .iter()
+        .find_map(|extension| file_name.strip_suffix(extension)?.strip_suffix('.'))
+    else {
+        return false;
+    };
+
+    // Segments after the first one have their number appended, like "name.2.h264"
+    let first_segment_name = name
+        .rsplit_once('.')
+        .filter(|(_, segment)| !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()))
+        .map(|(name, _)| name);
+
+    matches_filename_template(name, template)
+        || first_segment_name.is_some_and(|name| matches_filename_template(name, template))
+}
+
+// Recordings saved in the directory with the filename template, including the segments of split
+// recordings. Other video files in the directory are ignored
+pub fn recording_files(dir: &Path, template: &str) -> Vec<(PathBuf, fs::Metadata)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            let metadata = entry.metadata().ok()?;
+
+            let is_recording = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .is_some_and(|file_name| is_recording_file_name(file_name, template));
+
+            (is_recording && metadata.is_file()).then_some((path, metadata))
+        })
+        .collect()
+}
+
+// The permission bits are not enough to tell, the directory could be on a read-only mount or be
+// restricted by an ACL
+pub fn check_dir_writable(dir: &Path) -> Result<()> {
+    let probe_path = dir.join(".alvr_write_test");
+    File::create(&probe_path)
+        .with_context(|| format!("Recording directory {} is not writable", dir.display()))?;
+    fs::remove_file(&probe_path).ok();
+
+    Ok(())
+}
+
+// Subdirectories are not included
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+// Space available to the current user on the volume containing the path
+fn available_space(disks: &Disks, path: &Path) -> Option<u64> {
+    let path = path::absolute(path).ok()?;
+
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+pub fn storage_usage_json(connection_context: &ConnectionContext) -> String {
+    let log_dir = FILESYSTEM_LAYOUT.get().unwrap().log_dir.clone();
+    let capture_config = SESSION_MANAGER.read().settings().extra.capture.clone();
+    let recording_dir = crate::recording_dir(connection_context, &capture_config);
+
+    let recordings_bytes =
+        recording_files(&recording_dir, &capture_config.recording_filename_template)
+            .iter()
+            .map(|(_, metadata)| metadata.len())
+            .sum::<u64>();
+
+    let disks = Disks::new_with_refreshed_list();
+
+    // If the recordings are saved in the log directory, they are also counted in log_dir_bytes
+    json::json!({
+        "log_dir": log_dir,
+        "log_dir_bytes": dir_size(&log_dir),
+        "log_dir_available_bytes": available_space(&disks, &log_dir),
+        "recording_dir": recording_dir,
+        "recordings_bytes": recordings_bytes,
+        "recording_dir_available_bytes": available_space(&disks, &recording_dir),
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_recording_file_name() {
+        let template = "recording.{timestamp}";
+        assert!(is_recording_file_name(
+            "recording.2024-05-01.10-20-30.h264",
+            template
+        ));
+        assert!(is_recording_file_name(
+            "recording.2024-05-01.10-20-30.2.h265",
+            template
+        ));
+        assert!(!is_recording_file_name(
+            "recording.2024-05-01.10-20-30.mp4",
+            template
+        ));
+        assert!(!is_recording_file_name("holiday.h264", template));
+
+        let template = "{hostname}_{index}";
+        assert!(is_recording_file_name("quest.client_12.av1", template));
+        assert!(!is_recording_file_name("quest.client_final.av1", template));
+
+        assert!(!is_recording_file_name("holiday.h264", "{timestamp}"));
+    }
+}
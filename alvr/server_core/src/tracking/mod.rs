@@ -1,9 +1,11 @@
 mod body;
 mod face;
+mod osc_output;
 mod vmc;
 
 pub use body::*;
 pub use face::*;
+pub use osc_output::*;
 pub use vmc::*;
 
 use crate::{
@@ -350,6 +352,12 @@ pub fn tracking_loop(
         .into_option()
         .and_then(|config| VMCSink::new(config).ok());
 
+    let mut osc_output_sink = initial_settings
+        .headset
+        .osc_output
+        .as_option()
+        .and_then(|config| OscOutputSink::new(config).ok());
+
     while is_streaming() {
         let data = match tracking_receiver.recv(STREAMING_RECV_TIMEOUT) {
             Ok(tracking) => tracking,
@@ -537,6 +545,20 @@ pub fn tracking_loop(
             }
         }
 
+        if let Some(sink) = &mut osc_output_sink {
+            let tracking_manager_lock = ctx.tracking_manager.read();
+            let device_motions = device_motion_keys
+                .iter()
+                .filter_map(|id| {
+                    Some((
+                        *id,
+                        tracking_manager_lock.get_device_motion(*id, timestamp)?,
+                    ))
+                })
+                .collect::<Vec<_>>();
+            sink.send_tracking(&device_motions);
+        }
+
         let track_body = matches!(
             SESSION_MANAGER.read().settings().headset.body_tracking,
             Switch::Enabled(BodyTrackingConfig { tracked: true, .. })
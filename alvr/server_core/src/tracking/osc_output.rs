@@ -0,0 +1,82 @@
+use alvr_common::{anyhow::Result, DeviceMotion, BUTTON_INFO, DEVICE_ID_TO_PATH};
+use alvr_packets::{ButtonEntry, ButtonValue};
+use alvr_session::OscOutputConfig;
+use rosc::{OscMessage, OscPacket, OscType};
+use std::{
+    net::UdpSocket,
+    time::{Duration, Instant},
+};
+
+const ADDRESS_PREFIX: &str = "/alvr";
+
+// Sends poses to /alvr/<device path>/pose as position xyz and orientation xyzw, and button values
+// to /alvr/<button path>
+pub struct OscOutputSink {
+    socket: UdpSocket,
+    pose_interval: Duration,
+    last_pose_instant: Option<Instant>,
+}
+
+impl OscOutputSink {
+    pub fn new(config: &OscOutputConfig) -> Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(format!("{}:{}", config.host, config.port))?;
+
+        Ok(Self {
+            socket,
+            pose_interval: Duration::from_secs_f32(1.0 / config.max_pose_rate_hz.max(1) as f32),
+            last_pose_instant: None,
+        })
+    }
+
+    fn send_osc_message(&self, path: String, args: Vec<OscType>) {
+        self.socket
+            .send(
+                &rosc::encoder::encode(&OscPacket::Message(OscMessage { addr: path, args }))
+                    .unwrap(),
+            )
+            .ok();
+    }
+
+    // Poses over the configured rate are dropped
+    pub fn send_tracking(&mut self, device_motions: &[(u64, DeviceMotion)]) {
+        if self
+            .last_pose_instant
+            .is_some_and(|instant| instant.elapsed() < self.pose_interval)
+        {
+            return;
+        }
+        self.last_pose_instant = Some(Instant::now());
+
+        for (id, motion) in device_motions {
+            if let Some(path) = DEVICE_ID_TO_PATH.get(id) {
+                let pose = &motion.pose;
+                self.send_osc_message(
+                    format!("{ADDRESS_PREFIX}{path}/pose"),
+                    vec![
+                        OscType::Float(pose.position.x),
+                        OscType::Float(pose.position.y),
+                        OscType::Float(pose.position.z),
+                        OscType::Float(pose.orientation.x),
+                        OscType::Float(pose.orientation.y),
+                        OscType::Float(pose.orientation.z),
+                        OscType::Float(pose.orientation.w),
+                    ],
+                );
+            }
+        }
+    }
+
+    pub fn send_buttons(&self, entries: &[ButtonEntry]) {
+        for entry in entries {
+            if let Some(info) = BUTTON_INFO.get(&entry.path_id) {
+                let value = match entry.value {
+                    ButtonValue::Binary(value) => OscType::Bool(value),
+                    ButtonValue::Scalar(value) => OscType::Float(value),
+                };
+
+                self.send_osc_message(format!("{ADDRESS_PREFIX}{}", info.path), vec![value]);
+            }
+        }
+    }
+}
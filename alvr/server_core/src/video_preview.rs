@@ -0,0 +1,175 @@
+use crate::ServerCoreEvent;
+use alvr_common::{
+    anyhow::{Context, Result},
+    parking_lot::Mutex,
+    warn,
+};
+use bytes::Bytes;
+use image::{
+    codecs::jpeg::JpegEncoder,
+    imageops::{self, FilterType},
+    ExtendedColorType, RgbImage,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast;
+
+// The preview is meant for quick looks, a low framerate keeps the readback and encoding cheap
+const FRAME_INTERVAL: Duration = Duration::from_millis(200);
+// No frame is rendered while the headset is not streaming, the request is then repeated
+const FRAME_REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+const JPEG_QUALITY: u8 = 80;
+// Fits remote viewing over limited uplinks, a few tens of KB per frame
+const LOW_QUALITY_MAX_WIDTH: u32 = 960;
+const LOW_QUALITY_JPEG_QUALITY: u8 = 50;
+// Slow subscribers skip frames instead of falling behind
+const CHANNEL_CAPACITY: usize = 2;
+
+#[derive(Clone, Copy)]
+pub enum PreviewQuality {
+    Full,
+    // Downscaled and more compressed
+    Low,
+}
+
+fn encode_jpeg(rgb: &[u8], width: u32, height: u32, quality: u8) -> Result<Bytes> {
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, quality).encode(
+        rgb,
+        width,
+        height,
+        ExtendedColorType::Rgb8,
+    )?;
+
+    Ok(jpeg.into())
+}
+
+fn encode_low_quality(rgb: Vec<u8>, width: u32, height: u32) -> Result<Bytes> {
+    if width <= LOW_QUALITY_MAX_WIDTH {
+        return encode_jpeg(&rgb, width, height, LOW_QUALITY_JPEG_QUALITY);
+    }
+
+    let image = RgbImage::from_raw(width, height, rgb).context("Invalid frame size")?;
+    let scaled_height = (height * LOW_QUALITY_MAX_WIDTH / width).max(1);
+    let scaled = imageops::resize(
+        &image,
+        LOW_QUALITY_MAX_WIDTH,
+        scaled_height,
+        FilterType::Triangle,
+    );
+
+    encode_jpeg(
+        &scaled,
+        LOW_QUALITY_MAX_WIDTH,
+        scaled_height,
+        LOW_QUALITY_JPEG_QUALITY,
+    )
+}
+
+// JPEG frames of the rendered output. The frames are captured by the encoder only while there are
+// subscribers, and each quality is encoded only if it has subscribers
+pub struct VideoPreview {
+    events_sender: mpsc::Sender<ServerCoreEvent>,
+    full_sender: broadcast::Sender<Bytes>,
+    low_quality_sender: broadcast::Sender<Bytes>,
+    // Set from the frame request until the frame is encoded, so only one frame is in flight
+    frame_request_time: Mutex<Option<Instant>>,
+    capture_loop_running: AtomicBool,
+}
+
+impl VideoPreview {
+    pub fn new(events_sender: mpsc::Sender<ServerCoreEvent>) -> Self {
+        Self {
+            events_sender,
+            full_sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            low_quality_sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            frame_request_time: Mutex::new(None),
+            capture_loop_running: AtomicBool::new(false),
+        }
+    }
+
+    pub fn sender(&self, quality: PreviewQuality) -> &broadcast::Sender<Bytes> {
+        match quality {
+            PreviewQuality::Full => &self.full_sender,
+            PreviewQuality::Low => &self.low_quality_sender,
+        }
+    }
+
+    fn has_subscribers(&self) -> bool {
+        self.full_sender.receiver_count() > 0 || self.low_quality_sender.receiver_count() > 0
+    }
+
+    // Must be called from the tokio runtime, after subscribing to a sender, so a stopping capture
+    // loop sees the new subscriber
+    pub fn start_capture(self: &Arc<Self>) {
+        if !self.capture_loop_running.swap(true, Ordering::SeqCst) {
+            tokio::spawn(Arc::clone(self).capture_loop());
+        }
+    }
+
+    pub fn subscribe(self: &Arc<Self>, quality: PreviewQuality) -> broadcast::Receiver<Bytes> {
+        let receiver = self.sender(quality).subscribe();
+        self.start_capture();
+
+        receiver
+    }
+
+    async fn capture_loop(self: Arc<Self>) {
+        loop {
+            if !self.has_subscribers() {
+                self.capture_loop_running.store(false, Ordering::SeqCst);
+
+                // A subscriber that came in before the flag was cleared did not start a new loop
+                if !self.has_subscribers() || self.capture_loop_running.swap(true, Ordering::SeqCst)
+                {
+                    return;
+                }
+            }
+
+            {
+                let mut request_time = self.frame_request_time.lock();
+                if !request_time.is_some_and(|time| time.elapsed() < FRAME_REQUEST_TIMEOUT) {
+                    *request_time = Some(Instant::now());
+
+                    self.events_sender
+                        .send(ServerCoreEvent::CapturePreviewFrame)
+                        .ok();
+                }
+            }
+
+            tokio::time::sleep(FRAME_INTERVAL).await;
+        }
+    }
+
+    // Encoding is slow, so it is done on a separate thread to not stall the rendering
+    pub fn send_frame(self: &Arc<Self>, width: u32, height: u32, rgb: Vec<u8>) {
+        let this = Arc::clone(self);
+        thread::spawn(move || {
+            if this.full_sender.receiver_count() > 0 {
+                match encode_jpeg(&rgb, width, height, JPEG_QUALITY) {
+                    Ok(jpeg) => {
+                        this.full_sender.send(jpeg).ok();
+                    }
+                    Err(e) => warn!("Failed to encode preview frame: {e}"),
+                }
+            }
+
+            if this.low_quality_sender.receiver_count() > 0 {
+                match encode_low_quality(rgb, width, height) {
+                    Ok(jpeg) => {
+                        this.low_quality_sender.send(jpeg).ok();
+                    }
+                    Err(e) => warn!("Failed to encode low quality preview frame: {e}"),
+                }
+            }
+
+            *this.frame_request_time.lock() = None;
+        });
+    }
+}
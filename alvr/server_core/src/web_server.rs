@@ -1,92 +1,1622 @@
 use crate::{
-    logging_backend::LOGGING_EVENTS_SENDER, ConnectionContext, ServerCoreEvent, FILESYSTEM_LAYOUT,
-    SESSION_MANAGER,
+    logging_backend::{
+        self, SharedEvent, ERROR_EVENTS_SENDER, LOGGING_EVENTS_SENDER, STATISTICS_EVENTS_SENDER,
+    },
+    remote_log::DROPPED_REMOTE_LOG_LINES,
+    statistics::{Metric, MetricKind},
+    video_preview::PreviewQuality,
+    ConnectionContext, ServerCoreEvent, VideoMirrorPacket, FILESYSTEM_LAYOUT, SESSION_MANAGER,
 };
 use alvr_common::{
-    anyhow::{self, Result},
-    error, info, log, ConnectionState,
+    anyhow::{self, bail, Result},
+    debug, error, info,
+    log::{self, LevelFilter},
+    once_cell::sync::Lazy,
+    parking_lot::{Condvar, Mutex},
+    settings_schema::Switch,
+    warn, ConnectionState, LifecycleState, LogSeverity, BUTTON_INFO, HAND_LEFT_ID, HAND_LEFT_PATH,
+    HAND_RIGHT_ID, HAND_RIGHT_PATH,
+};
+use alvr_events::{ButtonEvent, Event, EventType};
+use alvr_packets::{
+    AudioDeviceKind, ButtonEntry, ButtonValue, ClientListAction, Haptics, RegisteredDriver,
+    ServerRequest, ServerVersionInfo,
 };
-use alvr_events::{ButtonEvent, EventType};
-use alvr_packets::{ButtonEntry, ClientListAction, ServerRequest};
-use bytes::Buf;
-use futures::SinkExt;
+use alvr_session::WebServerTlsConfig;
+use bytes::Bytes;
+use chrono::{DateTime, Local, SecondsFormat};
+use flate2::{write::GzEncoder, Compression};
+use futures::{Sink, SinkExt, Stream, StreamExt};
 use headers::{
     AccessControlAllowHeaders, AccessControlAllowMethods, AccessControlRequestHeaders,
-    AccessControlRequestMethod, HeaderMapExt,
+    AccessControlRequestMethod, ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch, LastModified,
 };
 use hyper::{
     header::{
-        self, HeaderName, HeaderValue, ACCESS_CONTROL_ALLOW_ORIGIN, CACHE_CONTROL, CONTENT_TYPE,
+        self, HeaderName, HeaderValue, ACCEPT_ENCODING, ACCEPT_RANGES, ACCESS_CONTROL_ALLOW_ORIGIN,
+        CACHE_CONTROL, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE,
     },
+    http::response,
+    server::conn::Http,
     service, Body, Method, Request, Response, StatusCode,
 };
+use percent_encoding::percent_decode_str;
 use serde::de::DeserializeOwned;
 use serde_json as json;
-use std::{net::SocketAddr, sync::Arc};
-use tokio::sync::broadcast::{self, error::RecvError};
-use tokio_tungstenite::{tungstenite::protocol, WebSocketStream};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, Write},
+    net::{self, IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncSeekExt},
+    net::TcpListener,
+    sync::{
+        broadcast::{self, error::RecvError},
+        watch,
+    },
+    time::MissedTickBehavior,
+};
+use tokio_rustls::{rustls, TlsAcceptor};
+use tokio_tungstenite::{
+    tungstenite::protocol::{self, frame::coding::CloseCode},
+    WebSocketStream,
+};
+use tokio_util::{either::Either, io::ReaderStream};
+
+const X_ALVR: &str = "X-ALVR";
+
+static START_INSTANT: Lazy<Instant> = Lazy::new(Instant::now);
+
+// Messages lost by all websocket clients because they couldn't keep up with the broadcast
+static DROPPED_WEBSOCKET_MESSAGES: AtomicU64 = AtomicU64::new(0);
+
+// Each video mirror message is prefixed with a header, see VideoMirrorPacket
+const VIDEO_MIRROR_FRAMED_PROTOCOL: &str = "alvr-video-mirror-framed";
+
+const WEB_SERVER_PORT_FALLBACK_ATTEMPTS: u16 = 10;
+
+// First byte of a TLS record containing a handshake message (the ClientHello)
+const TLS_HANDSHAKE_RECORD_TYPE: u8 = 0x16;
+
+const MAX_IDR_BURST_COUNT: u32 = 30;
+const MAX_IDR_BURST_INTERVAL: Duration = Duration::from_secs(1);
+
+const BUTTON_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+// Limits how often /api/set-buttons updates reach the driver. Updates over the limit are coalesced
+// to the latest value of each button and sent when the next window starts
+struct ButtonRateLimiter {
+    window_start: Instant,
+    updates: u32,
+    pending: HashMap<u64, ButtonValue>,
+}
+
+static BUTTON_RATE_LIMITER: Lazy<Mutex<ButtonRateLimiter>> = Lazy::new(|| {
+    Mutex::new(ButtonRateLimiter {
+        window_start: Instant::now(),
+        updates: 0,
+        pending: HashMap::new(),
+    })
+});
+
+const MAX_HAPTICS_DURATION: Duration = Duration::from_secs(5);
+const MAX_HAPTICS_FREQUENCY: f32 = 1000.0;
+
+fn reply(code: StatusCode) -> Result<Response<Body>> {
+    Ok(Response::builder().status(code).body(Body::empty())?)
+}
+
+fn query_param(request: &Request<Body>, name: &str) -> Option<String> {
+    form_urlencoded::parse(request.uri().query()?.as_bytes())
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+// Filter for /api/events, set with the min_severity and types query parameters. A type matches
+// all events whose name starts with it, so "Statistics" selects StatisticsSummary.
+struct EventsFilter {
+    min_severity: Option<LogSeverity>,
+    types: Option<Vec<String>>,
+}
+
+impl EventsFilter {
+    fn from_request(request: &Request<Body>) -> Result<Self> {
+        let min_severity = query_param(request, "min_severity")
+            .map(|severity| match severity.to_lowercase().as_str() {
+                "debug" => Ok(LogSeverity::Debug),
+                "info" => Ok(LogSeverity::Info),
+                "warn" | "warning" => Ok(LogSeverity::Warning),
+                "error" => Ok(LogSeverity::Error),
+                _ => Err(anyhow::anyhow!("Invalid min_severity \"{severity}\"")),
+            })
+            .transpose()?;
+
+        let types = query_param(request, "types").map(|types| {
+            types
+                .split(',')
+                .map(|ty| ty.trim().to_lowercase())
+                .filter(|ty| !ty.is_empty())
+                .collect()
+        });
+
+        Ok(Self {
+            min_severity,
+            types,
+        })
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        if let Some(min_severity) = self.min_severity {
+            let severity = match &event.event_type {
+                EventType::Log(entry) => Some(entry.severity),
+                EventType::DebugGroup { .. } => Some(LogSeverity::Debug),
+                _ => None,
+            };
+
+            if severity.is_some_and(|severity| severity < min_severity) {
+                return false;
+            }
+        }
+
+        if let Some(types) = &self.types {
+            let name = event.event_type.name().to_lowercase();
+
+            return types.iter().any(|ty| name.starts_with(ty));
+        }
+
+        true
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn is_authorized(request: &Request<Body>) -> bool {
+    let Some(token) = SESSION_MANAGER
+        .read()
+        .settings()
+        .connection
+        .web_server_auth_token
+        .clone()
+    else {
+        return true;
+    };
+
+    let header_token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_owned);
+
+    // Browsers cannot set custom headers on WebSocket upgrades
+    let query_token = request
+        .headers()
+        .contains_key(header::SEC_WEBSOCKET_KEY)
+        .then(|| query_param(request, "token"))
+        .flatten();
+
+    header_token
+        .or(query_token)
+        .is_some_and(|t| constant_time_eq(t.as_bytes(), token.as_bytes()))
+}
+
+// Returns the request origin if it is present in the configured allowlist
+fn allowed_origin(request: &Request<Body>) -> Option<HeaderValue> {
+    let origin = request.headers().get(header::ORIGIN)?;
+
+    SESSION_MANAGER
+        .read()
+        .settings()
+        .connection
+        .web_server_allowed_origins
+        .iter()
+        .any(|allowed| allowed.as_bytes() == origin.as_bytes())
+        .then(|| origin.clone())
+}
+
+fn insert_cors_headers(response: &mut Response<Body>, allowed_origin: Option<HeaderValue>) {
+    let headers = response.headers_mut();
+
+    if let Some(origin) = allowed_origin {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+        headers.typed_insert(cors_allowed_methods());
+        headers.typed_insert(cors_allowed_headers());
+    } else if SESSION_MANAGER
+        .read()
+        .settings()
+        .connection
+        .web_server_allowed_origins
+        .is_empty()
+    {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    }
+}
+
+fn cors_allowed_methods() -> AccessControlAllowMethods {
+    [Method::GET, Method::POST, Method::OPTIONS]
+        .into_iter()
+        .collect()
+}
+
+fn cors_allowed_headers() -> AccessControlAllowHeaders {
+    [
+        CONTENT_TYPE,
+        header::AUTHORIZATION,
+        HeaderName::from_static("x-alvr"),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn cors_preflight_response(allowed_origin: HeaderValue) -> Result<Response<Body>> {
+    let mut response: Response<Body> = Response::builder()
+        .status(StatusCode::OK)
+        .header(CACHE_CONTROL, "no-cache, no-store, must-revalidate")
+        .header(ACCESS_CONTROL_ALLOW_ORIGIN, allowed_origin)
+        .body("".into())?;
+
+    let headers = response.headers_mut();
+    headers.typed_insert(cors_allowed_methods());
+    headers.typed_insert(cors_allowed_headers());
+
+    Ok(response)
+}
+
+fn accepts_gzip(request: &Request<Body>) -> bool {
+    request
+        .headers()
+        .get(ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value.split(',').any(|encoding| {
+                let mut parts = encoding.trim().split(';');
+                let name = parts.next().unwrap_or_default().trim();
+                let disabled = parts.any(|param| param.trim().replace(' ', "") == "q=0");
+
+                (name == "gzip" || name == "*") && !disabled
+            })
+        })
+}
+
+// Extension, content type, and whether the content is worth compressing
+const STATIC_CONTENT_TYPES: &[(&str, &str, bool)] = &[
+    ("html", "text/html; charset=utf-8", true),
+    ("js", "text/javascript; charset=utf-8", true),
+    ("css", "text/css; charset=utf-8", true),
+    ("json", "application/json", true),
+    ("wasm", "application/wasm", true),
+    ("svg", "image/svg+xml", true),
+    ("ico", "image/x-icon", true),
+    ("png", "image/png", false),
+    ("jpg", "image/jpeg", false),
+    ("jpeg", "image/jpeg", false),
+    ("woff2", "font/woff2", false),
+];
+
+fn static_content_type(path: &Path) -> (&'static str, bool) {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+
+    STATIC_CONTENT_TYPES
+        .iter()
+        .find(|(ext, _, _)| extension.as_deref() == Some(*ext))
+        .map(|(_, content_type, compressible)| (*content_type, *compressible))
+        .unwrap_or(("application/octet-stream", false))
+}
+
+static GZIP_CACHE: Lazy<Mutex<HashMap<PathBuf, (SystemTime, Bytes)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn gzip_cache_lookup(path: &Path, modified: SystemTime) -> Option<Bytes> {
+    GZIP_CACHE
+        .lock()
+        .get(path)
+        .filter(|(cached_modified, _)| *cached_modified == modified)
+        .map(|(_, compressed)| compressed.clone())
+}
+
+fn gzip_cached(path: &Path, modified: SystemTime, data: &[u8]) -> Result<Bytes> {
+    if let Some(compressed) = gzip_cache_lookup(path, modified) {
+        return Ok(compressed);
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    let compressed = Bytes::from(encoder.finish()?);
+
+    GZIP_CACHE
+        .lock()
+        .insert(path.to_owned(), (modified, compressed.clone()));
+
+    Ok(compressed)
+}
+
+// Bundlers name immutable assets like "name-0123abcd.js"
+fn is_hashed_asset(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.rsplit_once('-'))
+        .is_some_and(|(_, hash)| hash.len() >= 8 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// Requires the ALVR_EMBEDDED_DASHBOARD_DIR environment variable to point to the built dashboard
+#[cfg(feature = "embedded-dashboard")]
+static EMBEDDED_DASHBOARD: include_dir::Dir<'static> =
+    include_dir::include_dir!("$ALVR_EMBEDDED_DASHBOARD_DIR");
+
+// Response with the caching headers, and whether the client copy is still valid
+fn static_file_headers(
+    request: &Request<Body>,
+    path: &Path,
+    etag: ETag,
+    modified: Option<SystemTime>,
+) -> (response::Builder, bool) {
+    let cache_control = if is_hashed_asset(path) {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-cache"
+    };
+
+    let not_modified = if let Some(if_none_match) = request.headers().typed_get::<IfNoneMatch>() {
+        !if_none_match.precondition_passes(&etag)
+    } else if let (Some(if_modified_since), Some(modified)) =
+        (request.headers().typed_get::<IfModifiedSince>(), modified)
+    {
+        !if_modified_since.is_modified(modified)
+    } else {
+        false
+    };
+
+    let mut response = Response::builder().header(CACHE_CONTROL, cache_control);
+    let headers = response.headers_mut().unwrap();
+    headers.typed_insert(etag);
+    if let Some(modified) = modified {
+        headers.typed_insert(LastModified::from(modified));
+    }
+
+    (response, not_modified)
+}
+
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    Full,
+    // Inclusive
+    Partial { start: u64, end: u64 },
+    Unsatisfiable,
+}
+
+// Only single ranges are supported. The full content is sent for multiple ranges, as allowed by
+// RFC 9110
+fn parse_byte_range(header: Option<&str>, size: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|header| header.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+
+    let (start, end) = (start.trim(), end.trim());
+    let range = if start.is_empty() {
+        // Suffix range, the last bytes of the content
+        match end.parse::<u64>() {
+            Ok(0) => None,
+            Ok(suffix) => Some((size.saturating_sub(suffix), size.saturating_sub(1))),
+            Err(_) => return ByteRange::Full,
+        }
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return ByteRange::Full;
+        };
+        let end = if end.is_empty() {
+            size.saturating_sub(1)
+        } else {
+            match end.parse::<u64>() {
+                Ok(end) if end >= start => end.min(size.saturating_sub(1)),
+                _ => return ByteRange::Full,
+            }
+        };
+
+        Some((start, end))
+    };
+
+    match range {
+        Some((start, end)) if start < size => ByteRange::Partial { start, end },
+        _ => ByteRange::Unsatisfiable,
+    }
+}
+
+fn requested_byte_range(request: &Request<Body>, size: u64) -> ByteRange {
+    parse_byte_range(
+        request
+            .headers()
+            .get(RANGE)
+            .and_then(|value| value.to_str().ok()),
+        size,
+    )
+}
+
+fn range_not_satisfiable(size: u64) -> Result<Response<Body>> {
+    Ok(Response::builder()
+        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+        .header(CONTENT_RANGE, format!("bytes */{size}"))
+        .body(Body::empty())?)
+}
+
+// Adds the content headers, and returns whether the body must be compressed
+fn static_file_encoding(
+    request: &Request<Body>,
+    mut response: response::Builder,
+    path: &Path,
+    size: usize,
+) -> (response::Builder, bool) {
+    const GZIP_MIN_SIZE: usize = 1024;
+
+    let (content_type, compressible) = static_content_type(path);
+
+    response = response
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, content_type)
+        .header(ACCEPT_RANGES, "bytes");
+
+    let gzip = if compressible && size >= GZIP_MIN_SIZE {
+        response = response.header(header::VARY, "Accept-Encoding");
+
+        // Ranges are served from the uncompressed content
+        accepts_gzip(request) && !request.headers().contains_key(RANGE)
+    } else {
+        false
+    };
+    if gzip {
+        response = response.header(header::CONTENT_ENCODING, "gzip");
+    }
+
+    (response, gzip)
+}
+
+fn static_file_body(
+    request: &Request<Body>,
+    response: response::Builder,
+    path: &Path,
+    modified: SystemTime,
+    data: Bytes,
+) -> Result<Response<Body>> {
+    let (mut response, gzip) = static_file_encoding(request, response, path, data.len());
+
+    let body = if gzip {
+        gzip_cached(path, modified, &data)?
+    } else {
+        match requested_byte_range(request, data.len() as u64) {
+            ByteRange::Full => data,
+            ByteRange::Partial { start, end } => {
+                response = response
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(CONTENT_RANGE, format!("bytes {start}-{end}/{}", data.len()));
+
+                data.slice(start as usize..=end as usize)
+            }
+            ByteRange::Unsatisfiable => return range_not_satisfiable(data.len() as u64),
+        }
+    };
+
+    if request.method() == Method::HEAD {
+        return Ok(response
+            .header(CONTENT_LENGTH, body.len())
+            .body(Body::empty())?);
+    }
+
+    Ok(response.body(body.into())?)
+}
+
+// Used when the file is not found on disk. Returns the embedded copy if available
+#[cfg_attr(not(feature = "embedded-dashboard"), allow(unused_variables))]
+fn missing_static_file(
+    request: &Request<Body>,
+    relative_path: &str,
+    missing_response: impl FnOnce() -> Result<Response<Body>>,
+) -> Result<Response<Body>> {
+    #[cfg(feature = "embedded-dashboard")]
+    if let Some(file) = EMBEDDED_DASHBOARD.get_file(relative_path) {
+        let path = Path::new(relative_path);
+        let data = file.contents();
+        // Embedded files only change with the build
+        let etag = format!("W/\"{:x}-{}\"", data.len(), *alvr_common::ALVR_VERSION)
+            .parse::<ETag>()
+            .unwrap();
+
+        let (response, not_modified) = static_file_headers(request, path, etag, None);
+        if not_modified {
+            return Ok(response
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Body::empty())?);
+        }
+
+        return static_file_body(
+            request,
+            response,
+            path,
+            SystemTime::UNIX_EPOCH,
+            Bytes::from_static(data),
+        );
+    }
+
+    missing_response()
+}
+
+// Serves the web dashboard files. The on-disk copy takes precedence over the embedded one, so the
+// files can still be edited without rebuilding
+async fn static_file(request: &Request<Body>) -> Result<Response<Body>> {
+    let Ok(decoded_path) = percent_decode_str(request.uri().path()).decode_utf8() else {
+        return reply(StatusCode::BAD_REQUEST);
+    };
+    let relative_path = decoded_path.trim_start_matches('/');
+    let relative_path = if relative_path.is_empty() {
+        "index.html"
+    } else {
+        relative_path
+    };
+    if relative_path
+        .split(['/', '\\'])
+        .any(|component| component == "..")
+    {
+        return reply(StatusCode::FORBIDDEN);
+    }
+
+    let dashboard_dir = FILESYSTEM_LAYOUT.get().unwrap().dashboard_dir();
+    let Ok(root) = tokio::fs::canonicalize(&dashboard_dir).await else {
+        return missing_static_file(request, relative_path, || {
+            // This is an installation problem, not a bad request
+            let message = format!("Dashboard directory {} not found", dashboard_dir.display());
+            error!("{message}");
+
+            Ok(Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(message.into())?)
+        });
+    };
+    let Ok(path) = tokio::fs::canonicalize(root.join(relative_path)).await else {
+        return missing_static_file(request, relative_path, || reply(StatusCode::NOT_FOUND));
+    };
+    // Also catches symlinks pointing outside the dashboard directory
+    if !path.starts_with(&root) {
+        return reply(StatusCode::FORBIDDEN);
+    }
+
+    let Ok(metadata) = tokio::fs::metadata(&path).await else {
+        return missing_static_file(request, relative_path, || reply(StatusCode::NOT_FOUND));
+    };
+    if !metadata.is_file() {
+        return missing_static_file(request, relative_path, || reply(StatusCode::NOT_FOUND));
+    }
+
+    let modified = metadata.modified()?;
+    let modified_secs = modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // Weak because the same tag is used for both gzip and identity encodings
+    let etag = format!("W/\"{:x}-{modified_secs:x}\"", metadata.len())
+        .parse::<ETag>()
+        .unwrap();
+
+    let (response, not_modified) = static_file_headers(request, &path, etag, Some(modified));
+    if not_modified {
+        return Ok(response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())?);
+    }
+
+    // The file is not read. The compressed size is reported only if already known
+    if request.method() == Method::HEAD {
+        let size = metadata.len() as usize;
+        let (mut response, gzip) = static_file_encoding(request, response, &path, size);
+
+        let content_length = if gzip {
+            gzip_cache_lookup(&path, modified).map(|compressed| compressed.len())
+        } else {
+            match requested_byte_range(request, size as u64) {
+                ByteRange::Full => Some(size),
+                ByteRange::Partial { start, end } => {
+                    response = response
+                        .status(StatusCode::PARTIAL_CONTENT)
+                        .header(CONTENT_RANGE, format!("bytes {start}-{end}/{size}"));
+
+                    Some((end - start + 1) as usize)
+                }
+                ByteRange::Unsatisfiable => return range_not_satisfiable(size as u64),
+            }
+        };
+        if let Some(content_length) = content_length {
+            response = response.header(CONTENT_LENGTH, content_length);
+        }
+
+        return Ok(response.body(Body::empty())?);
+    }
+
+    let data = tokio::fs::read(&path).await?;
+
+    static_file_body(request, response, &path, modified, Bytes::from(data))
+}
+
+fn prometheus_text(metrics: &[Metric]) -> String {
+    let mut text = String::new();
+    for metric in metrics {
+        let kind = match metric.kind {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+        };
+
+        text += &format!(
+            "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n",
+            name = metric.name,
+            help = metric.help,
+            value = metric.value,
+        );
+    }
+
+    text
+}
+
+async fn download_file(request: &Request<Body>, path: &Path) -> Result<Response<Body>> {
+    let Ok(mut file) = tokio::fs::File::open(path).await else {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body("log file not found".into())?);
+    };
+    let size = file.metadata().await?.len();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    let response = Response::builder()
+        .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}\""),
+        )
+        .header(ACCEPT_RANGES, "bytes");
+
+    match requested_byte_range(request, size) {
+        ByteRange::Full => Ok(response.body(Body::wrap_stream(ReaderStream::new(file)))?),
+        ByteRange::Partial { start, end } => {
+            file.seek(io::SeekFrom::Start(start)).await?;
+
+            Ok(response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, format!("bytes {start}-{end}/{size}"))
+                .header(CONTENT_LENGTH, end - start + 1)
+                .body(Body::wrap_stream(ReaderStream::new(
+                    file.take(end - start + 1),
+                )))?)
+        }
+        ByteRange::Unsatisfiable => range_not_satisfiable(size),
+    }
+}
+
+// Returns None if the body is larger than the configured limit
+async fn read_body(request: Request<Body>) -> Result<Option<Bytes>> {
+    let max_size = SESSION_MANAGER
+        .read()
+        .settings()
+        .connection
+        .web_server_max_request_body_mb as usize
+        * 1024
+        * 1024;
+
+    // Rejects declared oversized bodies without reading them
+    if request
+        .headers()
+        .typed_get::<headers::ContentLength>()
+        .is_some_and(|length| length.0 > max_size as u64)
+    {
+        return Ok(None);
+    }
+
+    let mut body = request.into_body();
+    let mut data = Vec::new();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        if data.len() + chunk.len() > max_size {
+            return Ok(None);
+        }
+
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(Bytes::from(data)))
+}
+
+async fn from_request_body<T: DeserializeOwned>(request: Request<Body>) -> Result<Option<T>> {
+    read_body(request)
+        .await?
+        .map(|body| Ok(json::from_slice(&body)?))
+        .transpose()
+}
+
+fn payload_too_large(allowed_origin: &Option<HeaderValue>) -> Result<Response<Body>> {
+    let mut response = reply(StatusCode::PAYLOAD_TOO_LARGE)?;
+    insert_cors_headers(&mut response, allowed_origin.clone());
+
+    Ok(response)
+}
+
+// Set when the server shuts down, to close the websocket connections gracefully
+static SHUTDOWN_SENDER: Lazy<watch::Sender<bool>> = Lazy::new(|| watch::channel(false).0);
+
+pub fn notify_shutdown() {
+    SHUTDOWN_SENDER.send_replace(true);
+}
+
+// The returned watch::Ref is dropped right away, it must not be held across awaits since it is not
+// Send
+async fn wait_for_shutdown(shutdown_receiver: &mut watch::Receiver<bool>) {
+    shutdown_receiver
+        .wait_for(|shutting_down| *shutting_down)
+        .await
+        .ok();
+}
+
+// Open connections and websocket tasks, waited for by shutdown()
+static ACTIVE_TASKS: Mutex<usize> = Mutex::new(0);
+static ACTIVE_TASKS_DONE: Condvar = Condvar::new();
+
+struct ActiveTaskGuard;
+
+impl ActiveTaskGuard {
+    fn new() -> Self {
+        *ACTIVE_TASKS.lock() += 1;
+
+        Self
+    }
+}
+
+impl Drop for ActiveTaskGuard {
+    fn drop(&mut self) {
+        let mut active_tasks = ACTIVE_TASKS.lock();
+        *active_tasks -= 1;
+        if *active_tasks == 0 {
+            ACTIVE_TASKS_DONE.notify_all();
+        }
+    }
+}
+
+// Stops accepting connections, closes the websockets and waits for the in-flight requests to
+// complete, up to the timeout. Blocking, must not be called from the web server runtime
+pub fn shutdown(timeout: Duration) {
+    notify_shutdown();
+
+    let mut active_tasks = ACTIVE_TASKS.lock();
+    ACTIVE_TASKS_DONE.wait_while_for(&mut active_tasks, |count| *count > 0, timeout);
+    if *active_tasks > 0 {
+        warn!("Web server shutdown timed out with {active_tasks} connections still open");
+    }
+}
+
+const LAG_WARNING_INTERVAL: Duration = Duration::from_secs(5);
+// Limits how long an abandoned upgrade keeps the task and its receiver alive
+const WEBSOCKET_UPGRADE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Data from the priority receiver is sent first. Cancel safe, like broadcast::Receiver::recv()
+async fn recv_prioritized<T: Clone>(
+    priority_receiver: &mut Option<broadcast::Receiver<T>>,
+    data_receiver: &mut broadcast::Receiver<T>,
+) -> Result<T, RecvError> {
+    if let Some(receiver) = priority_receiver {
+        tokio::select! {
+            biased;
+            res = receiver.recv() => match res {
+                Err(RecvError::Closed) => *priority_receiver = None,
+                res => return res,
+            },
+            res = data_receiver.recv() => return res,
+        }
+    }
+
+    data_receiver.recv().await
+}
+
+// Data sources of a websocket connection, prepared before the upgrade completes
+struct WebsocketForwarder<T, F> {
+    data_receiver: broadcast::Receiver<T>,
+    priority_receiver: Option<broadcast::Receiver<T>>,
+    shutdown_receiver: watch::Receiver<bool>,
+    replay: Vec<T>,
+    message_builder: F,
+    heartbeat_interval: Duration,
+}
+
+impl<T: Clone, F: Fn(T) -> Option<protocol::Message>> WebsocketForwarder<T, F> {
+    // Takes ownership of the receivers so they are dropped as soon as the peer goes away,
+    // otherwise dead connections would keep accumulating on the broadcast channels
+    async fn run<E: std::fmt::Display>(
+        self,
+        mut ws_sink: impl Sink<protocol::Message, Error = E> + Unpin,
+        mut ws_stream: impl Stream<Item = Result<protocol::Message, E>> + Unpin,
+    ) {
+        let Self {
+            mut data_receiver,
+            mut priority_receiver,
+            mut shutdown_receiver,
+            replay,
+            message_builder,
+            heartbeat_interval,
+        } = self;
+
+        for message in replay.into_iter().filter_map(&message_builder) {
+            if ws_sink.send(message).await.is_err() {
+                return;
+            }
+        }
+
+        // Pings detect peers that disappeared without closing the connection
+        let mut heartbeat = tokio::time::interval(heartbeat_interval);
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut pong_pending = false;
+        let mut last_lag_warning = None::<Instant>;
+        let mut dropped_since_last_warning = 0;
+
+        loop {
+            tokio::select! {
+                res = recv_prioritized(&mut priority_receiver, &mut data_receiver) => match res {
+                    Ok(data) => {
+                        let Some(message) = message_builder(data) else {
+                            continue;
+                        };
+
+                        if let Err(e) = ws_sink.send(message).await {
+                            info!("Failed to send log with websocket: {e}");
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(count)) => {
+                        DROPPED_WEBSOCKET_MESSAGES.fetch_add(count, Ordering::Relaxed);
+                        dropped_since_last_warning += count;
+
+                        if !last_lag_warning.is_some_and(|instant| {
+                            instant.elapsed() < LAG_WARNING_INTERVAL
+                        }) {
+                            warn!(
+                                "Websocket client is too slow, {dropped_since_last_warning} \
+                                messages were dropped since the last warning. Consider increasing \
+                                the buffer capacity"
+                            );
+                            last_lag_warning = Some(Instant::now());
+                            dropped_since_last_warning = 0;
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = heartbeat.tick() => {
+                    if pong_pending {
+                        info!("Websocket peer stopped responding, closing connection");
+                        break;
+                    }
+
+                    if ws_sink.send(protocol::Message::Ping(Bytes::new())).await.is_err() {
+                        break;
+                    }
+                    pong_pending = true;
+                }
+                _ = wait_for_shutdown(&mut shutdown_receiver) => {
+                    // Lets clients tell apart a server shutdown from a lost connection
+                    ws_sink
+                        .send(protocol::Message::Close(Some(protocol::CloseFrame {
+                            code: CloseCode::Away,
+                            reason: "Server shutting down".into(),
+                        })))
+                        .await
+                        .ok();
+
+                    break;
+                }
+                message = ws_stream.next() => match message {
+                    Some(Ok(protocol::Message::Pong(_))) => pong_pending = false,
+                    Some(Ok(protocol::Message::Close(_)) | Err(_)) | None => break,
+                    Some(Ok(_)) => (),
+                },
+            }
+        }
+
+        drop(data_receiver);
+        drop(priority_receiver);
+
+        ws_sink.close().await.ok();
+    }
+}
+
+const MJPEG_BOUNDARY: &str = "alvr-frame";
+
+// Ends on shutdown, otherwise the graceful shutdown of the connection would wait for it
+fn mjpeg_stream(
+    receiver: broadcast::Receiver<Bytes>,
+) -> impl Stream<Item = Result<Bytes, io::Error>> {
+    futures::stream::unfold(
+        (receiver, SHUTDOWN_SENDER.subscribe()),
+        |(mut receiver, mut shutdown_receiver)| async move {
+            let jpeg = loop {
+                tokio::select! {
+                    res = receiver.recv() => match res {
+                        Ok(jpeg) => break jpeg,
+                        // Only the latest frame matters
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    },
+                    _ = wait_for_shutdown(&mut shutdown_receiver) => return None,
+                }
+            };
+
+            let mut part = format!(
+                "--{MJPEG_BOUNDARY}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                jpeg.len()
+            )
+            .into_bytes();
+            part.extend_from_slice(&jpeg);
+            part.extend_from_slice(b"\r\n");
+
+            Some((Ok(Bytes::from(part)), (receiver, shutdown_receiver)))
+        },
+    )
+}
+
+fn websocket<T: Clone + Send + 'static>(
+    request: Request<Body>,
+    sender: broadcast::Sender<T>,
+    priority_sender: Option<&broadcast::Sender<T>>,
+    replay: impl FnOnce() -> Vec<T>,
+    message_builder: impl Fn(T) -> Option<protocol::Message> + Send + Sync + 'static,
+) -> Result<Response<Body>> {
+    if let Some(key) = request.headers().typed_get::<headers::SecWebsocketKey>() {
+        let heartbeat_interval = Duration::from_secs(
+            SESSION_MANAGER
+                .read()
+                .settings()
+                .connection
+                .websocket_heartbeat_interval_s
+                .max(1),
+        );
+
+        // Subscribe before taking the replay snapshot so no data falls in between
+        let forwarder = WebsocketForwarder {
+            data_receiver: sender.subscribe(),
+            priority_receiver: priority_sender.map(|sender| sender.subscribe()),
+            shutdown_receiver: SHUTDOWN_SENDER.subscribe(),
+            replay: replay(),
+            message_builder,
+            heartbeat_interval,
+        };
+
+        let guard = ActiveTaskGuard::new();
+        tokio::spawn(async move {
+            let _guard = guard;
+
+            match tokio::time::timeout(WEBSOCKET_UPGRADE_TIMEOUT, hyper::upgrade::on(request)).await
+            {
+                Ok(Ok(upgraded)) => {
+                    let ws =
+                        WebSocketStream::from_raw_socket(upgraded, protocol::Role::Server, None)
+                            .await;
+                    let (ws_sink, ws_stream) = ws.split();
+
+                    forwarder.run(ws_sink, ws_stream).await;
+                }
+                Ok(Err(e)) => error!("{e}"),
+                Err(_) => debug!("Websocket upgrade timed out"),
+            }
+        });
+
+        let mut response = Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .body(Body::empty())?;
+
+        let h = response.headers_mut();
+        h.typed_insert(headers::Upgrade::websocket());
+        h.typed_insert(headers::SecWebsocketAccept::from(key));
+        h.typed_insert(headers::Connection::upgrade());
+
+        Ok(response)
+    } else {
+        reply(StatusCode::BAD_REQUEST)
+    }
+}
+
+// Returned as 404 by /api/dashboard-request
+#[derive(Debug)]
+struct UnknownClientError(String);
+
+impl std::fmt::Display for UnknownClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown client \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for UnknownClientError {}
+
+// Returned as 500 by /api/dashboard-request. Other errors are considered caused by the request
+#[derive(Debug)]
+struct InternalRequestError(anyhow::Error);
+
+impl std::fmt::Display for InternalRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for InternalRequestError {}
+
+fn internal_error(e: anyhow::Error) -> anyhow::Error {
+    InternalRequestError(e).into()
+}
+
+fn request_error_status(e: &anyhow::Error) -> StatusCode {
+    if e.is::<UnknownClientError>() {
+        StatusCode::NOT_FOUND
+    } else if e.is::<alvr_server_io::SessionRevisionConflict>() {
+        StatusCode::CONFLICT
+    } else if e.is::<InternalRequestError>() {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+fn json_error_response(status: StatusCode, error: json::Value) -> Result<Response<Body>> {
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(error.to_string().into())?)
+}
+
+fn connected_clients_count() -> usize {
+    SESSION_MANAGER
+        .read()
+        .client_list()
+        .values()
+        .filter(|info| {
+            matches!(
+                info.connection_state,
+                ConnectionState::Connected | ConnectionState::Streaming
+            )
+        })
+        .count()
+}
+
+// DriversList keeps carrying only the paths for existing consumers
+fn send_driver_list(list: Vec<RegisteredDriver>) {
+    alvr_events::send_event(EventType::DriversList(
+        list.iter().map(|driver| driver.path.clone()).collect(),
+    ));
+    alvr_events::send_event(EventType::DriversStatus(list));
+}
+
+// Returns the JSON response body, if any
+// With sync set, query requests also return their result, which is otherwise only sent as event
+fn handle_server_request(
+    connection_context: &ConnectionContext,
+    request: ServerRequest,
+    sync: bool,
+) -> Result<Option<String>> {
+    match request {
+        ServerRequest::Log(event) => {
+            let level = event.severity.into_log_level();
+            log::log!(level, "{}", event.content);
+        }
+        ServerRequest::GetLogs { max_entries } => {
+            return Ok(Some(json::to_string(&logging_backend::log_history(
+                max_entries,
+            ))?));
+        }
+        ServerRequest::SetLogLevel { level } => {
+            let Ok(level_filter) = level.parse::<LevelFilter>() else {
+                bail!("Invalid log level \"{level}\"");
+            };
+
+            logging_backend::set_log_level(level_filter);
+        }
+        ServerRequest::GetSettingsSchema => {
+            // The defaults in the schema are the builtin ones, not the current session values
+            let schema = alvr_session::Settings::schema(alvr_session::session_settings_default());
+
+            return Ok(Some(json::to_string(&schema)?));
+        }
+        ServerRequest::GetSession => {
+            let session = crate::SESSION_MANAGER.read().session().clone();
+            let response = sync.then(|| json::to_string(&session)).transpose()?;
+
+            alvr_events::send_event(EventType::Session(Box::new(session)));
+
+            return Ok(response);
+        }
+        ServerRequest::UpdateSession(session) => {
+            SESSION_MANAGER.write().update_session(*session, None)?;
+        }
+        ServerRequest::UpdateSessionChecked {
+            session,
+            expected_revision,
+        } => {
+            SESSION_MANAGER
+                .write()
+                .update_session(*session, Some(expected_revision))?;
+        }
+        ServerRequest::SetValues(descs) => {
+            SESSION_MANAGER.write().set_values(descs)?;
+        }
+        ServerRequest::PatchSession {
+            patch,
+            expected_revision,
+        } => {
+            SESSION_MANAGER
+                .write()
+                .patch_session(patch, expected_revision)?;
+        }
+        ServerRequest::ExportSession => {
+            return Ok(Some(json::to_string_pretty(
+                SESSION_MANAGER.read().session(),
+            )?));
+        }
+        ServerRequest::ImportSession { session } => {
+            SESSION_MANAGER.write().import_session(session)?;
+        }
+        ServerRequest::ResetSettings => {
+            // The client list and the OpenVR config are left untouched
+            SESSION_MANAGER.write().session_mut().session_settings =
+                alvr_session::session_settings_default();
+
+            info!("Settings reset to defaults. Restart SteamVR to apply all changes");
+
+            return Ok(Some(
+                json::json!({ "steamvr_restart_required": true }).to_string(),
+            ));
+        }
+        ServerRequest::UpdateClientList {
+            hostname,
+            mut action,
+        } => {
+            let mut session_manager = SESSION_MANAGER.write();
+            if let ClientListAction::AddManual { ip, .. } = &action {
+                session_manager.check_manual_ip(&hostname, *ip)?;
+            }
+            if matches!(action, ClientListAction::RemoveEntry) {
+                if let Some(entry) = session_manager.client_list().get(&hostname) {
+                    if entry.connection_state != ConnectionState::Disconnected {
+                        connection_context
+                            .clients_to_be_removed
+                            .lock()
+                            .insert(hostname.clone());
+
+                        action =
+                            ClientListAction::SetConnectionState(ConnectionState::Disconnecting)
+                    };
+                }
+            }
+
+            session_manager.update_client_list(hostname, action);
+        }
+        ServerRequest::RenameClient {
+            hostname,
+            display_name,
+        } => {
+            let mut session_manager = SESSION_MANAGER.write();
+            if !session_manager.client_list().contains_key(&hostname) {
+                return Err(UnknownClientError(hostname).into());
+            }
+
+            // An empty name restores the one reported by the client
+            let display_name = display_name.trim();
+            session_manager.update_client_list(
+                hostname,
+                ClientListAction::SetCustomName(
+                    (!display_name.is_empty()).then(|| display_name.to_owned()),
+                ),
+            );
+        }
+        ServerRequest::ReconnectClient { hostname } => {
+            let mut session_manager = SESSION_MANAGER.write();
+            let Some(entry) = session_manager.client_list().get(&hostname) else {
+                return Err(UnknownClientError(hostname).into());
+            };
+
+            // Once disconnected, the client is picked up again by the discovery loop
+            if matches!(
+                entry.connection_state,
+                ConnectionState::Connecting
+                    | ConnectionState::Connected
+                    | ConnectionState::Streaming
+            ) {
+                connection_context
+                    .clients_to_be_removed
+                    .lock()
+                    .remove(&hostname);
+
+                session_manager.update_client_list(
+                    hostname,
+                    ClientListAction::SetConnectionState(ConnectionState::Disconnecting),
+                );
+            }
+        }
+        ServerRequest::SetAudioState {
+            game_audio_muted,
+            microphone_muted,
+        } => {
+            let audio_config = SESSION_MANAGER.read().settings().audio.clone();
+            if game_audio_muted.is_some() && !audio_config.game_audio.enabled() {
+                bail!("Game audio is disabled in the settings");
+            }
+            if microphone_muted.is_some() && !audio_config.microphone.enabled() {
+                bail!("Microphone is disabled in the settings");
+            }
+
+            if let Some(muted) = game_audio_muted {
+                connection_context
+                    .game_audio_muted
+                    .store(muted, Ordering::Relaxed);
+            }
+            if let Some(muted) = microphone_muted {
+                connection_context
+                    .microphone_muted
+                    .store(muted, Ordering::Relaxed);
+            }
+
+            let game_audio_muted = connection_context.game_audio_muted.load(Ordering::Relaxed);
+            let microphone_muted = connection_context.microphone_muted.load(Ordering::Relaxed);
+            info!("Game audio muted: {game_audio_muted}, microphone muted: {microphone_muted}");
+            alvr_events::send_event(EventType::AudioState {
+                game_audio_muted,
+                microphone_muted,
+            });
+
+            return Ok(Some(
+                json::json!({
+                    "game_audio_muted": game_audio_muted,
+                    "microphone_muted": microphone_muted,
+                })
+                .to_string(),
+            ));
+        }
+        ServerRequest::RefreshAudioDevices => {
+            let list = SESSION_MANAGER.read().get_audio_devices_list()?;
+            let response = json::to_string(&list)?;
+
+            alvr_events::send_event(EventType::AudioDevices(list));
+
+            return Ok(Some(response));
+        }
+        ServerRequest::SetAudioDevice { kind, id } => {
+            if cfg!(target_os = "linux") {
+                bail!("Audio devices are managed by PipeWire on Linux");
+            }
+
+            let list = SESSION_MANAGER.read().get_audio_devices_list()?;
+            let devices = match kind {
+                AudioDeviceKind::Output => &list.output,
+                AudioDeviceKind::Input => &list.input,
+            };
+            if !devices.contains(&id) {
+                bail!("Audio device \"{id}\" not found");
+            }
+
+            SESSION_MANAGER.write().set_audio_device(kind, id.clone())?;
+
+            match kind {
+                AudioDeviceKind::Output => {
+                    connection_context
+                        .game_audio_device_generation
+                        .fetch_add(1, Ordering::Relaxed);
+                }
+                // The microphone sink is not changed, only the SteamVR default microphone
+                AudioDeviceKind::Input =>
+                {
+                    #[cfg(windows)]
+                    if connected_clients_count() > 0 {
+                        use alvr_session::{
+                            CustomAudioDeviceConfig, OpenvrPropKey, OpenvrProperty,
+                        };
+
+                        let device = alvr_audio::AudioDevice::new_input(Some(
+                            CustomAudioDeviceConfig::NameSubstring(id.clone()),
+                        ))?;
+                        connection_context
+                            .events_sender
+                            .send(ServerCoreEvent::SetOpenvrProperty {
+                                device_id: *alvr_common::HEAD_ID,
+                                prop: OpenvrProperty {
+                                    key: OpenvrPropKey::AudioDefaultRecordingDeviceIdString,
+                                    value: alvr_audio::get_windows_device_id(&device)?,
+                                },
+                            })
+                            .ok();
+                    }
+                }
+            }
+
+            info!("{kind:?} audio device set to {id}");
+            alvr_events::send_event(EventType::AudioDevices(list));
+        }
+        ServerRequest::GetAudioDevices => {
+            let list = crate::SESSION_MANAGER.read().get_audio_devices_list();
+            match list {
+                Ok(list) => {
+                    let response = sync.then(|| json::to_string(&list)).transpose()?;
+
+                    alvr_events::send_event(EventType::AudioDevices(list));
+
+                    return Ok(response);
+                }
+                Err(e) if sync => return Err(e),
+                Err(_) => (),
+            }
+        }
+        ServerRequest::GetConnectionStatistics => {
+            let mut response = if connected_clients_count() > 0 {
+                let statistics = connection_context
+                    .statistics_manager
+                    .read()
+                    .as_ref()
+                    .and_then(|manager| manager.last_summary().cloned());
+
+                json::json!({ "state": "connected", "statistics": statistics })
+            } else {
+                json::json!({ "state": "disconnected" })
+            };
+            response["dropped_websocket_messages"] =
+                DROPPED_WEBSOCKET_MESSAGES.load(Ordering::Relaxed).into();
+
+            return Ok(Some(response.to_string()));
+        }
+        ServerRequest::CaptureFrame => {
+            if !cfg!(target_os = "linux") {
+                bail!("Frame capture is only supported on Linux");
+            }
+
+            let path_prefix = PathBuf::from(
+                &SESSION_MANAGER
+                    .read()
+                    .settings()
+                    .extra
+                    .capture
+                    .capture_frame_dir,
+            )
+            .join(format!(
+                "alvr_frame_{}",
+                chrono::Local::now().format("%F.%H-%M-%S%.3f")
+            ));
+
+            connection_context
+                .events_sender
+                .send(ServerCoreEvent::CaptureFrame {
+                    path_prefix: path_prefix.clone(),
+                })
+                .ok();
+
+            // The files are written by the encoder thread once the next frame is rendered
+            let path_prefix = path_prefix.to_string_lossy();
+            return Ok(Some(
+                json::json!({
+                    "input": format!("{path_prefix}_input.png"),
+                    "output": format!("{path_prefix}_output.png"),
+                })
+                .to_string(),
+            ));
+        }
+        ServerRequest::InsertIdr => {
+            connection_context
+                .events_sender
+                .send(ServerCoreEvent::RequestIDR)
+                .ok();
+        }
+        ServerRequest::InsertIdrBurst { count, interval_ms } => {
+            if count == 0 || count > MAX_IDR_BURST_COUNT {
+                bail!("IDR burst count must be between 1 and {MAX_IDR_BURST_COUNT}");
+            }
+            let interval = Duration::from_millis(interval_ms);
+            if interval > MAX_IDR_BURST_INTERVAL {
+                bail!("IDR burst interval must be at most {MAX_IDR_BURST_INTERVAL:?}");
+            }
+
+            let events_sender = connection_context.events_sender.clone();
+            thread::spawn(move || {
+                for i in 0..count {
+                    if i > 0 {
+                        thread::sleep(interval);
+                    }
+
+                    if events_sender.send(ServerCoreEvent::RequestIDR).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        ServerRequest::StartRecording => {
+            *connection_context.video_recording_dir.lock() = None;
+
+            crate::create_recording_file(
+                connection_context,
+                crate::SESSION_MANAGER.read().settings(),
+            )?;
+        }
+        ServerRequest::StartRecordingWithOptions(options) => {
+            if let Some(dir) = &options.path {
+                if !dir.is_dir() {
+                    bail!("Recording directory {} does not exist", dir.display());
+                }
+                crate::storage::check_dir_writable(dir)?;
+            }
 
-pub const WS_BROADCAST_CAPACITY: usize = 256;
+            *connection_context.video_recording_dir.lock() = options.path;
 
-fn reply(code: StatusCode) -> Result<Response<Body>> {
-    Ok(Response::builder().status(code).body(Body::empty())?)
-}
+            crate::create_recording_file(
+                connection_context,
+                crate::SESSION_MANAGER.read().settings(),
+            )?;
+        }
+        ServerRequest::StopRecording => {
+            if let Some(recording) = connection_context.video_recording_file.lock().take() {
+                recording.finish();
+            }
+        }
+        ServerRequest::FirewallRules(action) => {
+            if let Err(e) = alvr_server_io::firewall_rules(action, FILESYSTEM_LAYOUT.get().unwrap())
+            {
+                return Err(internal_error(anyhow::anyhow!(
+                    "Setting firewall rules failed! code: {e}"
+                )));
+            } else {
+                info!("Setting firewall rules succeeded!");
+            }
+        }
+        ServerRequest::SetBitrate { mbps } => {
+            let mbps = SESSION_MANAGER.write().set_constant_bitrate(mbps);
 
-async fn from_request_body<T: DeserializeOwned>(request: Request<Body>) -> Result<T> {
-    Ok(json::from_reader(
-        hyper::body::aggregate(request).await?.reader(),
-    )?)
-}
+            info!("Bitrate set to {mbps} Mbps");
 
-fn websocket<T: Clone + Send + 'static>(
-    request: Request<Body>,
-    sender: broadcast::Sender<T>,
-    message_builder: impl Fn(T) -> protocol::Message + Send + Sync + 'static,
-) -> Result<Response<Body>> {
-    if let Some(key) = request.headers().typed_get::<headers::SecWebsocketKey>() {
-        tokio::spawn(async move {
-            match hyper::upgrade::on(request).await {
-                Ok(upgraded) => {
-                    let mut data_receiver = sender.subscribe();
+            return Ok(Some(json::json!({ "mbps": mbps }).to_string()));
+        }
+        ServerRequest::GetButtonPaths => {
+            let mut paths = BUTTON_INFO
+                .values()
+                .map(|info| info.path)
+                .collect::<Vec<_>>();
+            paths.sort_unstable();
 
-                    let mut ws =
-                        WebSocketStream::from_raw_socket(upgraded, protocol::Role::Server, None)
-                            .await;
+            return Ok(Some(json::to_string(&paths)?));
+        }
+        ServerRequest::GetServerInfo => {
+            return Ok(Some(crate::server_info::server_info_json().to_owned()));
+        }
+        ServerRequest::GetClientHistory { hostname } => {
+            let Some(client) = SESSION_MANAGER.read().client_list().get(&hostname).cloned() else {
+                return Err(UnknownClientError(hostname).into());
+            };
+
+            return Ok(Some(json::to_string(&client.connection_history)?));
+        }
+        ServerRequest::GetStorageUsage => {
+            return Ok(Some(crate::storage::storage_usage_json(connection_context)));
+        }
+        ServerRequest::GetDiscoveryStatus => {
+            return Ok(Some(crate::sockets::discovery_status_json()));
+        }
+        ServerRequest::GetAvailableEncoders => {
+            return Ok(Some(
+                crate::server_info::available_encoders_json().to_owned(),
+            ));
+        }
+        ServerRequest::TriggerHaptics {
+            path,
+            duration_ms,
+            frequency,
+            amplitude,
+        } => {
+            let device_id = alvr_common::hash_string(&path);
+            if device_id != *HAND_LEFT_ID && device_id != *HAND_RIGHT_ID {
+                bail!("Haptics device must be {HAND_LEFT_PATH} or {HAND_RIGHT_PATH}");
+            }
+            let duration = Duration::from_millis(duration_ms);
+            if duration.is_zero() || duration > MAX_HAPTICS_DURATION {
+                bail!("Haptics duration must be between 1ms and {MAX_HAPTICS_DURATION:?}");
+            }
+            if !(0.0..=MAX_HAPTICS_FREQUENCY).contains(&frequency) {
+                bail!("Haptics frequency must be between 0 and {MAX_HAPTICS_FREQUENCY}Hz");
+            }
+            if !(0.0..=1.0).contains(&amplitude) {
+                bail!("Haptics amplitude must be between 0 and 1");
+            }
 
-                    loop {
-                        match data_receiver.recv().await {
-                            Ok(data) => {
-                                if let Err(e) = ws.send(message_builder(data)).await {
-                                    info!("Failed to send log with websocket: {e}");
-                                    break;
-                                }
+            crate::send_haptics(
+                connection_context,
+                Haptics {
+                    device_id,
+                    duration,
+                    frequency,
+                    amplitude,
+                },
+            )?;
+        }
+        ServerRequest::GetFirewallStatus => {
+            let status = alvr_server_io::firewall_status(FILESYSTEM_LAYOUT.get().unwrap());
 
-                                ws.flush().await.ok();
-                            }
-                            Err(RecvError::Lagged(_)) => (),
-                            Err(RecvError::Closed) => break,
-                        }
-                    }
+            return Ok(Some(json::to_string(&status)?));
+        }
+        ServerRequest::RegisterAlvrDriver => {
+            alvr_server_io::driver_registration(
+                &[FILESYSTEM_LAYOUT
+                    .get()
+                    .unwrap()
+                    .openvr_driver_root_dir
+                    .clone()],
+                true,
+            )
+            .map_err(internal_error)?;
 
-                    ws.close(None).await.ok();
-                }
-                Err(e) => error!("{e}"),
+            if let Ok(list) = alvr_server_io::get_driver_list() {
+                send_driver_list(list);
             }
-        });
+        }
+        ServerRequest::UnregisterDriver(path) => {
+            alvr_server_io::driver_registration(&[path], false).map_err(internal_error)?;
 
-        let mut response = Response::builder()
-            .status(StatusCode::SWITCHING_PROTOCOLS)
-            .body(Body::empty())?;
+            if let Ok(list) = alvr_server_io::get_driver_list() {
+                send_driver_list(list);
+            }
+        }
+        ServerRequest::GetDriverList => match alvr_server_io::get_driver_list() {
+            Ok(list) => {
+                let response = sync.then(|| json::to_string(&list)).transpose()?;
 
-        let h = response.headers_mut();
-        h.typed_insert(headers::Upgrade::websocket());
-        h.typed_insert(headers::SecWebsocketAccept::from(key));
-        h.typed_insert(headers::Connection::upgrade());
+                send_driver_list(list);
 
-        Ok(response)
-    } else {
-        reply(StatusCode::BAD_REQUEST)
+                return Ok(response);
+            }
+            Err(e) if sync => return Err(internal_error(e)),
+            Err(_) => (),
+        },
+        // The recording is finalized and the logs flushed when the server core is dropped
+        ServerRequest::RestartSteamvr => {
+            connection_context
+                .events_sender
+                .send(ServerCoreEvent::RestartPending)
+                .map_err(|_| internal_error(anyhow::anyhow!("The server core is not running")))?;
+        }
+        ServerRequest::ShutdownSteamvr => {
+            connection_context
+                .events_sender
+                .send(ServerCoreEvent::ShutdownPending)
+                .map_err(|_| internal_error(anyhow::anyhow!("The server core is not running")))?;
+        }
+        ServerRequest::ShutdownServer => {
+            // Nothing is torn down if the shutdown cannot be requested
+            connection_context
+                .events_sender
+                .send(ServerCoreEvent::ShutdownPending)
+                .map_err(|_| internal_error(anyhow::anyhow!("The server core is not running")))?;
+
+            crate::prepare_server_exit(connection_context);
+        }
+        ServerRequest::RestartServer => {
+            // The server runs inside SteamVR, so it is relaunched by restarting SteamVR
+            connection_context
+                .events_sender
+                .send(ServerCoreEvent::RestartPending)
+                .map_err(|_| internal_error(anyhow::anyhow!("The server core is not running")))?;
+
+            crate::prepare_server_exit(connection_context);
+        }
     }
+
+    Ok(None)
 }
 
 async fn http_api(
@@ -100,10 +1630,15 @@ async fn http_api(
         .connection
         .allow_untrusted_http;
 
-    const X_ALVR: &str = "X-ALVR";
+    let allowed_origin = allowed_origin(&request);
 
     // A browser is asking for CORS info
     if request.method() == Method::OPTIONS {
+        // Origins in the allowlist don't need allow_untrusted_http
+        if let Some(origin) = allowed_origin {
+            return cors_preflight_response(origin);
+        }
+
         let bad_request: Response<Body> = Response::builder()
             .status(StatusCode::FORBIDDEN)
             .body("".into())?;
@@ -126,9 +1661,9 @@ async fn http_api(
         {
             let mut found_x_alvr = false;
             for header in requested_headers.iter() {
-                if header == HeaderName::from_static(X_ALVR) {
+                if header == HeaderName::from_static("x-alvr") {
                     found_x_alvr = true;
-                } else if header != CONTENT_TYPE {
+                } else if header != CONTENT_TYPE && header != header::AUTHORIZATION {
                     return Ok(bad_request);
                 }
             }
@@ -142,32 +1677,24 @@ async fn http_api(
             return Ok(bad_request);
         }
 
-        let allowed_methods = [Method::GET, Method::POST, Method::OPTIONS]
-            .into_iter()
-            .collect::<AccessControlAllowMethods>();
-        let allowed_headers = [CONTENT_TYPE, HeaderName::from_static(X_ALVR)]
-            .into_iter()
-            .collect::<AccessControlAllowHeaders>();
-
-        let mut response: Response<Body> = Response::builder()
-            .status(StatusCode::OK)
-            .header(CACHE_CONTROL, "no-cache, no-store, must-revalidate")
-            .header(ACCESS_CONTROL_ALLOW_ORIGIN, "*")
-            .body("".into())?;
-
-        let headers = response.headers_mut();
-        headers.typed_insert(allowed_methods);
-        headers.typed_insert(allowed_headers);
-
-        return Ok(response);
+        return cors_preflight_response(HeaderValue::from_static("*"));
     }
 
-    if request.method() != Method::POST && request.method() != Method::GET {
+    let is_static_file = !request.uri().path().starts_with("/api/");
+    // HEAD is supported only for static files
+    if request.method() != Method::POST
+        && request.method() != Method::GET
+        && !(request.method() == Method::HEAD && is_static_file)
+    {
         return Ok(Response::builder()
             .status(StatusCode::BAD_REQUEST)
             .body("invalid method".into())?);
     }
 
+    if is_static_file && (request.method() == Method::GET || request.method() == Method::HEAD) {
+        return static_file(&request).await;
+    }
+
     // This is the actual core part of cors
     // We require the X-ALVR header, but the browser forces a cors preflight
     // if the site tries to send a request with it set since it's not-whitelisted
@@ -180,165 +1707,316 @@ async fn http_api(
             .body("missing X-ALVR header".into())?);
     }
 
+    // /api/ping and /api/healthz are left open for health checks
+    if !matches!(request.uri().path(), "/api/ping" | "/api/healthz") && !is_authorized(&request) {
+        let mut response = reply(StatusCode::UNAUTHORIZED)?;
+        insert_cors_headers(&mut response, allowed_origin);
+
+        return Ok(response);
+    }
+
     let mut response = match request.uri().path() {
         // New unified requests
         "/api/dashboard-request" => {
-            if let Ok(request) = from_request_body::<ServerRequest>(request).await {
-                match request {
-                    ServerRequest::Log(event) => {
-                        let level = event.severity.into_log_level();
-                        log::log!(level, "{}", event.content);
-                    }
-                    ServerRequest::GetSession => {
-                        alvr_events::send_event(EventType::Session(Box::new(
-                            crate::SESSION_MANAGER.read().session().clone(),
-                        )));
-                    }
-                    ServerRequest::UpdateSession(session) => {
-                        *SESSION_MANAGER.write().session_mut() = *session
-                    }
-                    ServerRequest::SetValues(descs) => {
-                        SESSION_MANAGER.write().set_values(descs).ok();
-                    }
-                    ServerRequest::UpdateClientList {
-                        hostname,
-                        mut action,
-                    } => {
-                        let mut session_manager = SESSION_MANAGER.write();
-                        if matches!(action, ClientListAction::RemoveEntry) {
-                            if let Some(entry) = session_manager.client_list().get(&hostname) {
-                                if entry.connection_state != ConnectionState::Disconnected {
-                                    connection_context
-                                        .clients_to_be_removed
-                                        .lock()
-                                        .insert(hostname.clone());
-
-                                    action = ClientListAction::SetConnectionState(
-                                        ConnectionState::Disconnecting,
-                                    )
-                                };
-                            }
-                        }
+            let sync = query_param(&request, "sync").is_some_and(|value| value == "true");
+            let Some(body) = read_body(request).await? else {
+                return payload_too_large(&allowed_origin);
+            };
 
-                        session_manager.update_client_list(hostname, action);
-                    }
-                    ServerRequest::GetAudioDevices => {
-                        if let Ok(list) = crate::SESSION_MANAGER.read().get_audio_devices_list() {
-                            alvr_events::send_event(EventType::AudioDevices(list));
-                        }
-                    }
-                    ServerRequest::CaptureFrame => {
-                        connection_context
-                            .events_sender
-                            .send(ServerCoreEvent::CaptureFrame)
-                            .ok();
-                    }
-                    ServerRequest::InsertIdr => {
-                        connection_context
-                            .events_sender
-                            .send(ServerCoreEvent::RequestIDR)
-                            .ok();
-                    }
-                    ServerRequest::StartRecording => crate::create_recording_file(
-                        connection_context,
-                        crate::SESSION_MANAGER.read().settings(),
-                    ),
-                    ServerRequest::StopRecording => {
-                        *connection_context.video_recording_file.lock() = None
-                    }
-                    ServerRequest::FirewallRules(action) => {
-                        if let Err(e) =
-                            alvr_server_io::firewall_rules(action, FILESYSTEM_LAYOUT.get().unwrap())
-                        {
-                            error!("Setting firewall rules failed! code: {e}");
-                        } else {
-                            info!("Setting firewall rules succeeded!");
-                        }
-                    }
-                    ServerRequest::RegisterAlvrDriver => {
-                        alvr_server_io::driver_registration(
-                            &[FILESYSTEM_LAYOUT
-                                .get()
-                                .unwrap()
-                                .openvr_driver_root_dir
-                                .clone()],
-                            true,
-                        )
-                        .ok();
+            // A JSON array is processed as a batch, in order, stopping at the first failure
+            if body.iter().find(|c| !c.is_ascii_whitespace()) == Some(&b'[') {
+                match json::from_slice::<Vec<ServerRequest>>(&body) {
+                    Ok(requests) => {
+                        let mut response = reply(StatusCode::NO_CONTENT)?;
 
-                        if let Ok(list) = alvr_server_io::get_registered_drivers() {
-                            alvr_events::send_event(EventType::DriversList(list));
-                        }
-                    }
-                    ServerRequest::UnregisterDriver(path) => {
-                        alvr_server_io::driver_registration(&[path], false).ok();
+                        for (index, request) in requests.into_iter().enumerate() {
+                            if let Err(e) = handle_server_request(connection_context, request, sync)
+                            {
+                                error!("Dashboard request {index} failed: {e}");
 
-                        if let Ok(list) = alvr_server_io::get_registered_drivers() {
-                            alvr_events::send_event(EventType::DriversList(list));
+                                response = json_error_response(
+                                    request_error_status(&e),
+                                    json::json!({ "index": index, "error": e.to_string() }),
+                                )?;
+
+                                break;
+                            }
                         }
+
+                        response
                     }
-                    ServerRequest::GetDriverList => {
-                        if let Ok(list) = alvr_server_io::get_registered_drivers() {
-                            alvr_events::send_event(EventType::DriversList(list));
+                    Err(e) => json_error_response(
+                        StatusCode::BAD_REQUEST,
+                        json::json!({ "error": format!("Invalid request: {e}") }),
+                    )?,
+                }
+            } else {
+                match json::from_slice::<ServerRequest>(&body) {
+                    Ok(request) => match handle_server_request(connection_context, request, sync) {
+                        Ok(Some(response_json)) => Response::builder()
+                            .header(CONTENT_TYPE, "application/json")
+                            .body(response_json.into())?,
+                        Ok(None) => reply(StatusCode::NO_CONTENT)?,
+                        Err(e) => {
+                            error!("{e}");
+
+                            json_error_response(
+                                request_error_status(&e),
+                                json::json!({ "error": e.to_string() }),
+                            )?
                         }
-                    }
-                    ServerRequest::RestartSteamvr => {
-                        connection_context
-                            .events_sender
-                            .send(ServerCoreEvent::RestartPending)
-                            .ok();
-                    }
-                    ServerRequest::ShutdownSteamvr => {
-                        connection_context
-                            .events_sender
-                            .send(ServerCoreEvent::ShutdownPending)
-                            .ok();
-                    }
+                    },
+                    Err(e) => json_error_response(
+                        StatusCode::BAD_REQUEST,
+                        json::json!({ "error": format!("Invalid request: {e}") }),
+                    )?,
                 }
+            }
+        }
+        "/api/events" => match EventsFilter::from_request(&request) {
+            // Error logs are only sent on the priority bus, so the two never overlap
+            Ok(filter) => websocket(
+                request,
+                LOGGING_EVENTS_SENDER.clone(),
+                Some(&*ERROR_EVENTS_SENDER),
+                || {
+                    logging_backend::event_replay()
+                        .into_iter()
+                        .map(SharedEvent::new)
+                        .collect()
+                },
+                move |e| {
+                    filter
+                        .matches(&e.event)
+                        .then(|| protocol::Message::Text(e.json().into()))
+                },
+            )?,
+            Err(e) => Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(e.to_string().into())?,
+        },
+        "/api/statistics" => websocket(
+            request,
+            STATISTICS_EVENTS_SENDER.clone(),
+            None,
+            Vec::new,
+            |e| Some(protocol::Message::Text(e.json().into())),
+        )?,
+        // With ?quality=low, each binary message is a downscaled JPEG frame instead of the
+        // encoder output, at a lower framerate. The headset stream is not affected
+        "/api/video-mirror"
+            if query_param(&request, "quality").is_some_and(|quality| quality == "low") =>
+        {
+            if cfg!(target_os = "linux") {
+                let video_preview = &connection_context.video_preview;
 
-                reply(StatusCode::OK)?
+                let res = websocket(
+                    request,
+                    video_preview.sender(PreviewQuality::Low).clone(),
+                    None,
+                    Vec::new,
+                    |jpeg| Some(protocol::Message::Binary(jpeg)),
+                )?;
+                video_preview.start_capture();
+
+                res
             } else {
-                reply(StatusCode::BAD_REQUEST)?
+                Response::builder()
+                    .status(StatusCode::NOT_IMPLEMENTED)
+                    .body("Low quality video mirror is only supported on Linux".into())?
             }
         }
-        "/api/events" => websocket(request, LOGGING_EVENTS_SENDER.clone(), |e| {
-            protocol::Message::Text(json::to_string(&e).unwrap())
-        })?,
         "/api/video-mirror" => {
-            let sender = {
-                let mut sender_lock = connection_context.video_mirror_sender.lock();
-                if let Some(sender) = &mut *sender_lock {
-                    sender.clone()
-                } else {
-                    let (sender, _) = broadcast::channel(WS_BROADCAST_CAPACITY);
-                    *sender_lock = Some(sender.clone());
+            // Held while subscribing, so no frame is sent between the cache snapshot and the
+            // subscription
+            let mut sender_lock = connection_context.video_mirror_sender.lock();
+            let sender = if let Some(sender) = &mut *sender_lock {
+                sender.clone()
+            } else {
+                let capacity = SESSION_MANAGER
+                    .read()
+                    .settings()
+                    .connection
+                    .web_server_video_mirror_capacity;
+                let (sender, _) = broadcast::channel(capacity.max(1));
+                *sender_lock = Some(sender.clone());
 
-                    sender
-                }
+                sender
             };
 
-            if let Some(config) = &*connection_context.decoder_config.lock() {
-                sender.send(config.config_buffer.clone()).ok();
-            }
+            // Only the new subscriber needs the decoder config and the cached frames, the others
+            // already received them
+            let config_buffer = connection_context
+                .decoder_config
+                .lock()
+                .as_ref()
+                .map(|config| {
+                    VideoMirrorPacket::new(Duration::ZERO, false, true, &config.config_buffer)
+                });
+            let cached_frames = connection_context
+                .video_mirror_cache
+                .lock()
+                .as_ref()
+                .map(|cache| cache.frames.clone());
+            let request_idr = cached_frames.is_none();
 
-            let res = websocket(request, sender, protocol::Message::Binary)?;
+            // Old clients don't request any protocol and get the raw NALs
+            let framed = request
+                .headers()
+                .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .any(|protocol| protocol.trim() == VIDEO_MIRROR_FRAMED_PROTOCOL);
 
-            connection_context
-                .events_sender
-                .send(ServerCoreEvent::RequestIDR)
-                .ok();
+            let mut res = websocket(
+                request,
+                sender,
+                None,
+                move || {
+                    config_buffer
+                        .into_iter()
+                        .chain(cached_frames.into_iter().flatten())
+                        .collect()
+                },
+                // Every subscriber is sent the same buffer, only its reference count is bumped
+                move |packet| {
+                    Some(protocol::Message::Binary(if framed {
+                        packet.framed()
+                    } else {
+                        packet.data()
+                    }))
+                },
+            )?;
+            drop(sender_lock);
+
+            if framed && res.status() == StatusCode::SWITCHING_PROTOCOLS {
+                res.headers_mut().insert(
+                    header::SEC_WEBSOCKET_PROTOCOL,
+                    HeaderValue::from_static(VIDEO_MIRROR_FRAMED_PROTOCOL),
+                );
+            }
+
+            if request_idr {
+                connection_context
+                    .events_sender
+                    .send(ServerCoreEvent::RequestIDR)
+                    .ok();
+            }
 
             res
         }
+        // Decodable by browsers and players without the dashboard decoder, at a lower framerate.
+        // The frames are read back from the compositor, so this is only supported on Linux
+        "/api/video-mirror.mjpeg" => {
+            if cfg!(target_os = "linux") {
+                let receiver = connection_context
+                    .video_preview
+                    .subscribe(PreviewQuality::Full);
+
+                Response::builder()
+                    .header(
+                        CONTENT_TYPE,
+                        format!("multipart/x-mixed-replace; boundary={MJPEG_BOUNDARY}"),
+                    )
+                    .header(CACHE_CONTROL, "no-cache")
+                    .body(Body::wrap_stream(mjpeg_stream(receiver)))?
+            } else {
+                Response::builder()
+                    .status(StatusCode::NOT_IMPLEMENTED)
+                    .body("MJPEG preview is only supported on Linux".into())?
+            }
+        }
         "/api/set-buttons" => {
-            let button_entries = from_request_body::<Vec<ButtonEvent>>(request)
-                .await?
+            let Some(button_events) = from_request_body::<Vec<ButtonEvent>>(request).await? else {
+                return payload_too_large(&allowed_origin);
+            };
+
+            // Unknown paths would be silently ignored by the driver
+            let invalid_paths = button_events
+                .iter()
+                .filter(|b| !BUTTON_INFO.contains_key(&alvr_common::hash_string(&b.path)))
+                .map(|b| b.path.as_str())
+                .collect::<Vec<_>>();
+            if !invalid_paths.is_empty() {
+                let mut response = Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(
+                        json::json!({ "invalid_paths": invalid_paths })
+                            .to_string()
+                            .into(),
+                    )?;
+                insert_cors_headers(&mut response, allowed_origin);
+
+                return Ok(response);
+            }
+
+            let button_entries = button_events
                 .iter()
                 .map(|b| ButtonEntry {
                     path_id: alvr_common::hash_string(&b.path),
                     value: b.value,
                 })
-                .collect();
+                .collect::<Vec<_>>();
+
+            let max_updates = SESSION_MANAGER
+                .read()
+                .settings()
+                .connection
+                .web_server_max_button_updates_per_s
+                .as_option()
+                .copied();
+            if let Some(max_updates) = max_updates {
+                let mut limiter = BUTTON_RATE_LIMITER.lock();
+                if limiter.window_start.elapsed() >= BUTTON_RATE_LIMIT_WINDOW {
+                    limiter.window_start = Instant::now();
+                    limiter.updates = 0;
+                }
+
+                if limiter.updates >= max_updates {
+                    let flush_scheduled = !limiter.pending.is_empty();
+                    limiter.pending.extend(
+                        button_entries
+                            .into_iter()
+                            .map(|entry| (entry.path_id, entry.value)),
+                    );
+
+                    if !flush_scheduled {
+                        let deadline = limiter.window_start + BUTTON_RATE_LIMIT_WINDOW;
+                        let events_sender = connection_context.events_sender.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep_until(deadline.into()).await;
+
+                            let button_entries = BUTTON_RATE_LIMITER
+                                .lock()
+                                .pending
+                                .drain()
+                                .map(|(path_id, value)| ButtonEntry { path_id, value })
+                                .collect::<Vec<_>>();
+                            if !button_entries.is_empty() {
+                                events_sender
+                                    .send(ServerCoreEvent::Buttons(button_entries))
+                                    .ok();
+                            }
+                        });
+                    }
+
+                    let mut response = Response::builder()
+                        .status(StatusCode::TOO_MANY_REQUESTS)
+                        .header(header::RETRY_AFTER, BUTTON_RATE_LIMIT_WINDOW.as_secs())
+                        .body(Body::empty())?;
+                    insert_cors_headers(&mut response, allowed_origin);
+
+                    return Ok(response);
+                }
+
+                limiter.updates += 1;
+                // Newer values must not be overwritten by older coalesced ones
+                for entry in &button_entries {
+                    limiter.pending.remove(&entry.path_id);
+                }
+            }
 
             connection_context
                 .events_sender
@@ -358,8 +2036,85 @@ async fn http_api(
                 .header(header::CONTENT_TYPE, "application/json")
                 .body(latency.to_string().into())?
         }
-        "/api/version" => Response::builder().body(alvr_common::ALVR_VERSION.to_string().into())?,
+        "/api/metrics" => {
+            let connected_clients = connected_clients_count();
+
+            let mut metrics = vec![Metric {
+                name: "alvr_connected_clients",
+                help: "Number of connected clients",
+                kind: MetricKind::Gauge,
+                value: connected_clients as f64,
+            }];
+            metrics.push(Metric {
+                name: "alvr_dropped_websocket_messages_total",
+                help: "Messages dropped because a websocket client couldn't keep up",
+                kind: MetricKind::Counter,
+                value: DROPPED_WEBSOCKET_MESSAGES.load(Ordering::Relaxed) as f64,
+            });
+            metrics.push(Metric {
+                name: "alvr_dropped_remote_log_lines_total",
+                help: "Log lines dropped because the remote log collector was unreachable or too slow",
+                kind: MetricKind::Counter,
+                value: DROPPED_REMOTE_LOG_LINES.load(Ordering::Relaxed) as f64,
+            });
+            if let Some(manager) = &*connection_context.statistics_manager.read() {
+                metrics.extend(manager.metrics());
+            }
+
+            Response::builder()
+                .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+                .body(prometheus_text(&metrics).into())?
+        }
+        "/api/logs/download" => {
+            let log_to_disk = SESSION_MANAGER.read().settings().extra.logging.log_to_disk;
+            if log_to_disk {
+                download_file(&request, &FILESYSTEM_LAYOUT.get().unwrap().session_log()).await?
+            } else {
+                Response::builder().status(StatusCode::NOT_FOUND).body(
+                    "session log is disabled, enable \"Log to disk\" in the settings".into(),
+                )?
+            }
+        }
+        "/api/logs/crash" => {
+            download_file(&request, &FILESYSTEM_LAYOUT.get().unwrap().crash_log()).await?
+        }
+        "/api/version" => {
+            let version_info = ServerVersionInfo {
+                version: alvr_common::ALVR_VERSION.clone(),
+                git_commit: option_env!("ALVR_GIT_COMMIT").map(String::from),
+                protocol_id: alvr_common::protocol_id(),
+                target_os: std::env::consts::OS.into(),
+            };
+
+            Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(json::to_string(&version_info)?.into())?
+        }
         "/api/ping" => reply(StatusCode::OK)?,
+        "/api/healthz" => {
+            let driver_ready =
+                *connection_context.lifecycle_state.read() == LifecycleState::Resumed;
+            let last_video_frame_time =
+                connection_context.last_video_frame_time.lock().map(|time| {
+                    DateTime::<Local>::from(time).to_rfc3339_opts(SecondsFormat::Millis, false)
+                });
+
+            let health = json::json!({
+                "uptime_s": START_INSTANT.elapsed().as_secs(),
+                "driver_ready": driver_ready,
+                "client_connected": connected_clients_count() > 0,
+                "last_video_frame_time": last_video_frame_time,
+            });
+
+            Response::builder()
+                .status(if driver_ready {
+                    StatusCode::OK
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE
+                })
+                .header(CONTENT_TYPE, "application/json")
+                .body(health.to_string().into())?
+        }
         _ => reply(StatusCode::NOT_FOUND)?,
     };
 
@@ -367,41 +2122,309 @@ async fn http_api(
         CACHE_CONTROL,
         HeaderValue::from_str("no-cache, no-store, must-revalidate")?,
     );
-    response
-        .headers_mut()
-        .insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+    insert_cors_headers(&mut response, allowed_origin);
 
     Ok(response)
 }
 
-pub async fn web_server(connection_context: Arc<ConnectionContext>) -> Result<()> {
-    let web_server_port = crate::SESSION_MANAGER
+async fn handle_request(
+    connection_context: &ConnectionContext,
+    request: Request<Body>,
+) -> Result<Response<Body>> {
+    let access_log_entry = match &SESSION_MANAGER
         .read()
         .settings()
-        .connection
-        .web_server_port;
+        .extra
+        .logging
+        .web_server_access_log
+    {
+        Switch::Enabled(config) if config.include_ping || request.uri().path() != "/api/ping" => {
+            Some((
+                request.method().clone(),
+                request.uri().path().to_owned(),
+                Instant::now(),
+            ))
+        }
+        _ => None,
+    };
+
+    let res = http_api(connection_context, request).await;
+    if let Err(e) = &res {
+        alvr_common::show_e(e);
+    }
+
+    if let Some((method, path, start)) = access_log_entry {
+        let status = match &res {
+            Ok(response) => response.status().as_u16().to_string(),
+            Err(_) => "error".into(),
+        };
+
+        debug!(
+            "{method} {path} {status} {:.1}ms",
+            start.elapsed().as_secs_f32() * 1000.0
+        );
+    }
 
-    let service = service::make_service_fn(move |_| {
+    res
+}
+
+fn load_tls_config(config: &WebServerTlsConfig) -> Result<rustls::ServerConfig> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(&config.cert_path)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        bail!("No certificate found in {}", config.cert_path);
+    }
+
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(&config.key_path)?))?
+        .ok_or_else(|| anyhow::anyhow!("No private key found in {}", config.key_path))?;
+
+    let mut server_config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)?;
+    // WebSocket upgrades are only supported over HTTP/1.1
+    server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    Ok(server_config)
+}
+
+fn bind_listener(address: SocketAddr, port_fallback: bool) -> Result<net::TcpListener> {
+    let attempts = if port_fallback {
+        WEB_SERVER_PORT_FALLBACK_ATTEMPTS
+    } else {
+        1
+    };
+
+    for port in (address.port()..).take(attempts as usize) {
+        match net::TcpListener::bind(SocketAddr::new(address.ip(), port)) {
+            Ok(listener) => {
+                if port != address.port() {
+                    warn!(
+                        "Web server port {} is already in use, using port {port} instead. Set \
+                        web_server_port to {port} so the dashboard can connect",
+                        address.port()
+                    );
+                }
+                listener.set_nonblocking(true)?;
+
+                return Ok(listener);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => continue,
+            Err(e) => bail!("Failed to bind the web server to {address}: {e}"),
+        }
+    }
+
+    if port_fallback {
+        bail!(
+            "Web server ports {} to {} are already in use. Close the programs using them or \
+            change web_server_port in the connection settings",
+            address.port(),
+            address.port().saturating_add(attempts - 1)
+        )
+    } else {
+        bail!(
+            "Web server port {} is already in use, probably by another program or ALVR instance. \
+            Close it, change web_server_port in the connection settings or enable \
+            web_server_port_fallback",
+            address.port()
+        )
+    }
+}
+
+async fn serve(
+    connection_context: Arc<ConnectionContext>,
+    listener: net::TcpListener,
+    tls_acceptor: Option<TlsAcceptor>,
+) -> Result<()> {
+    let listener = TcpListener::from_std(listener)?;
+    let mut shutdown_receiver = SHUTDOWN_SENDER.subscribe();
+
+    loop {
+        let (stream, peer_address) = tokio::select! {
+            res = listener.accept() => match res {
+                Ok(pair) => pair,
+                Err(e) => {
+                    debug!("Failed to accept web server connection: {e}");
+                    continue;
+                }
+            },
+            // The listener is dropped to free the port, open connections are drained by their tasks
+            _ = wait_for_shutdown(&mut shutdown_receiver) => return Ok(()),
+        };
+
+        let guard = ActiveTaskGuard::new();
+        let tls_acceptor = tls_acceptor.clone();
         let connection_context = Arc::clone(&connection_context);
-        async move {
-            Ok::<_, anyhow::Error>(service::service_fn(move |request| {
+        let mut shutdown_receiver = shutdown_receiver.clone();
+        tokio::spawn(async move {
+            let _guard = guard;
+
+            let service = service::service_fn(move |request| {
                 let connection_context = Arc::clone(&connection_context);
-                async move {
-                    let res = http_api(&connection_context, request).await;
-                    if let Err(e) = &res {
-                        alvr_common::show_e(e);
+                async move { handle_request(&connection_context, request).await }
+            });
+
+            let stream = if let Some(acceptor) = tls_acceptor {
+                // Peek the first byte to tell apart TLS from plain HTTP. Plain HTTP is still
+                // accepted from the local machine, which is what the dashboard uses. The dashboard
+                // connects to the bind address if set, so the peer can also be a non-loopback
+                // local IP.
+                let mut first_byte = [0];
+                let is_tls = matches!(stream.peek(&mut first_byte).await, Ok(1))
+                    && first_byte[0] == TLS_HANDSHAKE_RECORD_TYPE;
+
+                if is_tls {
+                    match acceptor.accept(stream).await {
+                        Ok(stream) => Either::Left(stream),
+                        Err(e) => {
+                            debug!("TLS handshake with {peer_address} failed: {e}");
+                            return;
+                        }
                     }
+                } else if peer_address.ip().is_loopback()
+                    || stream
+                        .local_addr()
+                        .is_ok_and(|local_address| local_address.ip() == peer_address.ip())
+                {
+                    Either::Right(stream)
+                } else {
+                    debug!("Rejected plain HTTP connection from {peer_address}");
+                    return;
+                }
+            } else {
+                Either::Right(stream)
+            };
 
-                    res
+            let connection = Http::new()
+                .serve_connection(stream, service)
+                .with_upgrades();
+            tokio::pin!(connection);
+
+            let res = tokio::select! {
+                res = &mut connection => res,
+                _ = wait_for_shutdown(&mut shutdown_receiver) => {
+                    // Lets the in-flight request complete, then closes the connection
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
                 }
-            }))
+            };
+
+            if let Err(e) = res {
+                debug!("Web server connection with {peer_address} closed with error: {e}");
+            }
+        });
+    }
+}
+
+pub async fn web_server(connection_context: Arc<ConnectionContext>) -> Result<()> {
+    Lazy::force(&START_INSTANT);
+    // Left set by a previous server instance in the same process
+    SHUTDOWN_SENDER.send_replace(false);
+
+    let (web_server_bind, web_server_port, port_fallback, tls_config) = {
+        let session_manager_lock = SESSION_MANAGER.read();
+        let connection = &session_manager_lock.settings().connection;
+
+        (
+            connection.web_server_bind.clone(),
+            connection.web_server_port,
+            connection.web_server_port_fallback,
+            connection.web_server_tls.clone(),
+        )
+    };
+
+    let bind_ip = web_server_bind.parse::<IpAddr>().map_err(|e| {
+        anyhow::anyhow!("Invalid web server bind address \"{web_server_bind}\": {e}")
+    })?;
+    let listener = bind_listener(SocketAddr::new(bind_ip, web_server_port), port_fallback)?;
+
+    let tls_acceptor = if let Switch::Enabled(config) = tls_config {
+        match load_tls_config(&config) {
+            Ok(tls_config) => Some(TlsAcceptor::from(Arc::new(tls_config))),
+            Err(e) => {
+                error!("Failed to load web server TLS certificate, using plain HTTP: {e}");
+                None
+            }
         }
-    });
+    } else {
+        None
+    };
 
-    Ok(hyper::Server::bind(&SocketAddr::new(
-        "0.0.0.0".parse().unwrap(),
-        web_server_port,
-    ))
-    .serve(service)
-    .await?)
+    serve(connection_context, listener, tls_acceptor).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{channel::mpsc, stream};
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range(None, 100), ByteRange::Full);
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-9"), 100),
+            ByteRange::Partial { start: 0, end: 9 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=90-"), 100),
+            ByteRange::Partial { start: 90, end: 99 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-10"), 100),
+            ByteRange::Partial { start: 90, end: 99 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=50-500"), 100),
+            ByteRange::Partial { start: 50, end: 99 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=100-"), 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-1,5-6"), 100),
+            ByteRange::Full
+        );
+        assert_eq!(parse_byte_range(Some("bytes=9-0"), 100), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("items=0-9"), 100), ByteRange::Full);
+    }
+
+    fn test_forwarder(
+        data_receiver: broadcast::Receiver<u32>,
+        shutdown_receiver: watch::Receiver<bool>,
+    ) -> WebsocketForwarder<u32, impl Fn(u32) -> Option<protocol::Message>> {
+        WebsocketForwarder {
+            data_receiver,
+            priority_receiver: None,
+            shutdown_receiver,
+            replay: vec![],
+            message_builder: |data: u32| Some(protocol::Message::Text(data.to_string().into())),
+            heartbeat_interval: Duration::from_secs(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_receivers_dropped_on_disconnect() {
+        let (sender, _) = broadcast::channel::<u32>(16);
+        let (_shutdown_sender, shutdown_receiver) = watch::channel(false);
+
+        for _ in 0..100 {
+            // Peer closed the connection
+            let (ws_sink, _ws_receiver) = mpsc::unbounded();
+            test_forwarder(sender.subscribe(), shutdown_receiver.clone())
+                .run(ws_sink, stream::empty())
+                .await;
+            assert_eq!(sender.receiver_count(), 0);
+
+            // Peer disappeared and sending fails
+            let (ws_sink, ws_receiver) = mpsc::unbounded();
+            drop(ws_receiver);
+            let forwarder = test_forwarder(sender.subscribe(), shutdown_receiver.clone());
+            sender.send(0).unwrap();
+            forwarder.run(ws_sink, stream::pending()).await;
+            assert_eq!(sender.receiver_count(), 0);
+        }
+    }
 }
@@ -0,0 +1,102 @@
+use alvr_common::{once_cell::sync::Lazy, warn, ConnectionState};
+use alvr_session::Settings;
+use serde::Serialize;
+use std::{
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+    time::Duration,
+};
+
+const QUEUE_CAPACITY: usize = 64;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(1), Duration::from_secs(5)];
+
+#[derive(Serialize)]
+struct ConnectionStateEvent {
+    hostname: String,
+    state: ConnectionState,
+    // Lets the receiver tell when the state changed, requests can be delayed by retries
+    timestamp: String,
+}
+
+struct WebhookRequest {
+    url: String,
+    body: String,
+}
+
+// Requests are sent one at a time from a single thread, so they arrive in the order of the state
+// changes. None if the thread could not be started
+static REQUEST_SENDER: Lazy<Option<SyncSender<WebhookRequest>>> = Lazy::new(|| {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+
+    match thread::Builder::new()
+        .name("webhook".into())
+        .spawn(move || request_loop(receiver))
+    {
+        Ok(_) => Some(sender),
+        Err(e) => {
+            warn!("Failed to start webhook thread: {e}");
+
+            None
+        }
+    }
+});
+
+fn request_loop(receiver: Receiver<WebhookRequest>) {
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Failed to create webhook client: {e}");
+            return;
+        }
+    };
+
+    for WebhookRequest { url, body } in receiver {
+        let mut attempt = 0;
+        loop {
+            let res = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send()
+                .and_then(|response| response.error_for_status());
+
+            match res {
+                Ok(_) => break,
+                Err(e) => {
+                    if let Some(delay) = RETRY_DELAYS.get(attempt) {
+                        thread::sleep(*delay);
+                        attempt += 1;
+                    } else {
+                        warn!("Failed to send connection state webhook to {url}: {e}");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The request is queued and sent from a separate thread so the state change is not blocked
+pub fn send_connection_state(settings: &Settings, hostname: &str, state: ConnectionState) {
+    let Some(url) = settings.connection.connection_state_webhook_url.clone() else {
+        return;
+    };
+
+    let body = serde_json::to_string(&ConnectionStateEvent {
+        hostname: hostname.to_owned(),
+        state,
+        timestamp: chrono::Local::now().to_rfc3339(),
+    })
+    .unwrap();
+
+    if let Some(sender) = &*REQUEST_SENDER {
+        // Disconnected only if the client could not be created, which was already reported
+        if let Err(TrySendError::Full(_)) = sender.try_send(WebhookRequest { url, body }) {
+            warn!("Webhook queue is full, dropping connection state change of {hostname}");
+        }
+    }
+}
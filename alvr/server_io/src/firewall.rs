@@ -1,11 +1,14 @@
 use crate::openvrpaths;
-use alvr_packets::FirewallRulesAction;
+use alvr_packets::{FirewallRulesAction, FirewallStatus};
 use std::{
     env, fs,
     path::{Path, PathBuf},
     process::Command,
 };
 
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
 fn netsh_add_rule_command_string(rule_name: &str, program_path: &Path) -> String {
     format!(
         "netsh advfirewall firewall add rule name=\"{}\" dir=in program=\"{}\" action=allow",
@@ -18,6 +21,8 @@ fn netsh_delete_rule_command_string(rule_name: &str) -> String {
     format!("netsh advfirewall firewall delete rule name=\"{rule_name}\"")
 }
 
+const WINDOWS_RULE_NAME: &str = "SteamVR ALVR vrserver";
+
 // Errors:
 // 1: firewall rule is already set
 // 126: pkexec request dismissed
@@ -50,7 +55,7 @@ pub fn firewall_rules(
             format!(
                 "{}\n{}",
                 netsh_add_rule_command_string(
-                    "SteamVR ALVR vrserver",
+                    WINDOWS_RULE_NAME,
                     &openvrpaths::steamvr_root_dir()
                         .map_err(|_| -1)?
                         .join("bin")
@@ -58,7 +63,7 @@ pub fn firewall_rules(
                         .join("vrserver.exe")
                 ),
                 netsh_add_rule_command_string(
-                    "SteamVR ALVR vrserver",
+                    WINDOWS_RULE_NAME,
                     &openvrpaths::steamvr_root_dir()
                         .map_err(|_| -1)?
                         .join("bin")
@@ -67,7 +72,7 @@ pub fn firewall_rules(
                 ),
             )
         } else {
-            netsh_delete_rule_command_string("SteamVR ALVR vrserver")
+            netsh_delete_rule_command_string(WINDOWS_RULE_NAME)
         };
         fs::write(&script_path, firewall_rules_script_content).map_err(|_| -1)?;
 
@@ -84,3 +89,43 @@ pub fn firewall_rules(
         Err(exit_status.code().unwrap())
     }
 }
+
+// Does not require admin privileges, except for some Linux firewalls
+pub fn firewall_status(filesystem_layout: &alvr_filesystem::Layout) -> FirewallStatus {
+    let status = if cfg!(target_os = "linux") {
+        Command::new("bash")
+            .arg(
+                PathBuf::from("../").join(
+                    filesystem_layout
+                        .firewall_script_dir
+                        .join("alvr_fw_config.sh"),
+                ),
+            )
+            .arg("status")
+            .status()
+    } else {
+        let mut command = Command::new("netsh");
+        command.args([
+            "advfirewall",
+            "firewall",
+            "show",
+            "rule",
+            &format!("name={WINDOWS_RULE_NAME}"),
+        ]);
+
+        #[cfg(windows)]
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+        command.status()
+    };
+
+    match status.ok().and_then(|status| status.code()) {
+        Some(0) => FirewallStatus::Active,
+        Some(4) if cfg!(target_os = "linux") => FirewallStatus::RequiresAdmin,
+        Some(5) if cfg!(target_os = "linux") => FirewallStatus::Inactive,
+        Some(99) if cfg!(target_os = "linux") => FirewallStatus::NoFirewall,
+        // netsh fails if no rule matches the name
+        Some(1) if !cfg!(target_os = "linux") => FirewallStatus::Inactive,
+        _ => FirewallStatus::Unknown,
+    }
+}
@@ -8,26 +8,79 @@ pub use openvrpaths::*;
 
 use alvr_common::{
     anyhow::{bail, Result},
-    error, info, ConnectionState,
+    error, info, ConnectionState, ALVR_VERSION,
 };
 use alvr_events::EventType;
-use alvr_packets::{AudioDevicesList, ClientListAction, PathSegment, PathValuePair};
-use alvr_session::{ClientConnectionConfig, SessionConfig, Settings};
+use alvr_packets::{
+    AudioDeviceKind, AudioDevicesList, ClientListAction, PathSegment, PathValuePair,
+};
+use alvr_session::{
+    BitrateModeDefaultVariant, ClientConnectionConfig, ConnectionHistoryEntry,
+    CustomAudioDeviceConfigDefaultVariant, MicrophoneDevicesConfigDefaultVariant, SessionConfig,
+    Settings, MAX_CONNECTION_HISTORY_ENTRIES,
+};
 use serde_json as json;
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, VecDeque},
     fmt::{self, Debug},
     fs,
+    net::IpAddr,
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
+pub const MIN_BITRATE_MBPS: u64 = 1;
+pub const MAX_BITRATE_MBPS: u64 = 1000;
+
 fn save_session(session: &SessionConfig, path: &Path) -> Result<()> {
     fs::write(path, json::to_string_pretty(session)?)?;
 
     Ok(())
 }
 
+// See RFC 7386. Objects are merged recursively, any other value replaces the target
+fn apply_merge_patch(target: &mut json::Value, patch: json::Value) {
+    let json::Value::Object(patch_fields) = patch else {
+        *target = patch;
+        return;
+    };
+
+    if !target.is_object() {
+        *target = json::Value::Object(json::Map::new());
+    }
+    let target_fields = target.as_object_mut().unwrap();
+
+    for (name, value) in patch_fields {
+        if value.is_null() {
+            target_fields.remove(&name);
+        } else {
+            apply_merge_patch(
+                target_fields.entry(name).or_insert(json::Value::Null),
+                value,
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SessionRevisionConflict {
+    pub expected: u64,
+    pub current: u64,
+}
+
+impl fmt::Display for SessionRevisionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Session revision {} is outdated, the current revision is {}",
+            self.expected, self.current
+        )
+    }
+}
+
+impl std::error::Error for SessionRevisionConflict {}
+
 // SessionConfig wrapper that saves session.json on destruction.
 pub struct SessionLock<'a> {
     session_desc: &'a mut SessionConfig,
@@ -50,6 +103,8 @@ impl DerefMut for SessionLock<'_> {
 
 impl Drop for SessionLock<'_> {
     fn drop(&mut self) {
+        self.session_desc.revision += 1;
+
         if let Some(session_path) = self.session_path {
             save_session(self.session_desc, session_path).ok();
         }
@@ -59,6 +114,9 @@ impl Drop for SessionLock<'_> {
     }
 }
 
+// Called with the session lock held, so it must not access the session manager directly
+pub type ConnectionStateListener = Box<dyn Fn(&Settings, &str, ConnectionState) + Send + Sync>;
+
 // Correct usage:
 // SessionManager should be used behind a Mutex. Each write of the session should be preceded by a
 // read, within the same lock.
@@ -68,6 +126,7 @@ pub struct ServerSessionManager {
     session_config: SessionConfig,
     settings: Settings,
     session_path: Option<PathBuf>,
+    connection_state_listener: Option<ConnectionStateListener>,
 }
 
 impl ServerSessionManager {
@@ -84,9 +143,17 @@ impl ServerSessionManager {
             session_config: session_config.clone(),
             settings: session_config.to_settings(),
             session_path,
+            connection_state_listener: None,
         }
     }
 
+    pub fn set_connection_state_listener(
+        &mut self,
+        listener: impl Fn(&Settings, &str, ConnectionState) + Send + Sync + 'static,
+    ) {
+        self.connection_state_listener = Some(Box::new(listener));
+    }
+
     fn load_session(session_path: &Path, config_dir: &Path) -> SessionConfig {
         let session_string = fs::read_to_string(session_path).unwrap_or_default();
 
@@ -151,6 +218,32 @@ impl ServerSessionManager {
         &self.settings
     }
 
+    fn check_revision(&self, expected_revision: Option<u64>) -> Result<()> {
+        match expected_revision {
+            Some(expected) if expected != self.session_config.revision => {
+                Err(SessionRevisionConflict {
+                    expected,
+                    current: self.session_config.revision,
+                }
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn update_session(
+        &mut self,
+        mut session_config: SessionConfig,
+        expected_revision: Option<u64>,
+    ) -> Result<()> {
+        self.check_revision(expected_revision)?;
+
+        session_config.revision = self.session_config.revision;
+        *self.session_mut() = session_config;
+
+        Ok(())
+    }
+
     // Note: "value" can be any session subtree, in json format.
     pub fn set_values(&mut self, descs: Vec<PathValuePair>) -> Result<()> {
         let mut session_json = serde_json::to_value(self.session_config.clone()).unwrap();
@@ -179,7 +272,9 @@ impl ServerSessionManager {
         }
 
         // session_json has been updated
+        let revision = self.session_config.revision;
         self.session_config = serde_json::from_value(session_json)?;
+        self.session_config.revision = revision + 1;
         self.settings = self.session_config.to_settings();
 
         if let Some(session_path) = &self.session_path {
@@ -191,16 +286,123 @@ impl ServerSessionManager {
         Ok(())
     }
 
+    // The session is left untouched if the patched session is not valid
+    pub fn patch_session(
+        &mut self,
+        patch: json::Value,
+        expected_revision: Option<u64>,
+    ) -> Result<()> {
+        self.check_revision(expected_revision)?;
+
+        let mut session_json = json::to_value(&self.session_config)?;
+        apply_merge_patch(&mut session_json, patch);
+
+        let session_config = match json::from_value::<SessionConfig>(session_json) {
+            Ok(session_config) => session_config,
+            Err(e) => bail!("Invalid session patch: {e}"),
+        };
+
+        self.update_session(session_config, None)
+    }
+
+    // Switches to constant bitrate. The bitrate is a real-time setting, so it's also applied to the
+    // current stream. Returns the clamped value.
+    pub fn set_constant_bitrate(&mut self, mbps: u64) -> u64 {
+        let mbps = mbps.clamp(MIN_BITRATE_MBPS, MAX_BITRATE_MBPS);
+
+        let mut session = self.session_mut();
+        let mode = &mut session.session_settings.video.bitrate.mode;
+        mode.variant = BitrateModeDefaultVariant::ConstantMbps;
+        mode.ConstantMbps = mbps;
+
+        mbps
+    }
+
+    pub fn set_audio_device(&mut self, kind: AudioDeviceKind, name: String) -> Result<()> {
+        let audio = &self.session_config.session_settings.audio;
+        match kind {
+            AudioDeviceKind::Output if !audio.game_audio.enabled => {
+                bail!("Game audio is disabled in the settings")
+            }
+            AudioDeviceKind::Input
+                if !audio.microphone.enabled
+                    || !matches!(
+                        audio.microphone.content.devices.variant,
+                        MicrophoneDevicesConfigDefaultVariant::Custom
+                    ) =>
+            {
+                bail!("The microphone must be enabled and set to custom devices")
+            }
+            _ => (),
+        }
+
+        let mut session = self.session_mut();
+        let audio = &mut session.session_settings.audio;
+        let device = match kind {
+            AudioDeviceKind::Output => {
+                audio.game_audio.content.device.set = true;
+
+                &mut audio.game_audio.content.device.content
+            }
+            AudioDeviceKind::Input => &mut audio.microphone.content.devices.Custom.source,
+        };
+        device.variant = CustomAudioDeviceConfigDefaultVariant::NameSubstring;
+        device.NameSubstring = name;
+
+        Ok(())
+    }
+
+    // The client list is kept since it is specific to this machine
+    pub fn import_session(&mut self, session_json: json::Value) -> Result<()> {
+        let version = session_json
+            .get("server_version")
+            .and_then(|version| version.as_str())
+            .unwrap_or_default();
+        if version != ALVR_VERSION.to_string() {
+            bail!(
+                "Cannot import session: version \"{version}\" does not match server version \"{}\"",
+                *ALVR_VERSION
+            );
+        }
+
+        let mut session_config = json::from_value::<SessionConfig>(session_json)?;
+        session_config.client_connections = self.session_config.client_connections.clone();
+
+        self.update_session(session_config, None)
+    }
+
     pub fn client_list(&self) -> &HashMap<String, ClientConnectionConfig> {
         &self.session_config.client_connections
     }
 
+    // Errors if the IP cannot be used to reach the client or if it already belongs to another client
+    pub fn check_manual_ip(&self, hostname: &str, ip: IpAddr) -> Result<()> {
+        let is_broadcast = matches!(ip, IpAddr::V4(ip) if ip.is_broadcast());
+        if ip.is_unspecified() || ip.is_multicast() || is_broadcast {
+            bail!("{ip} is not a valid client address");
+        }
+
+        if let Some(other_hostname) = self
+            .session_config
+            .client_connections
+            .iter()
+            .find(|(other, config)| *other != hostname && config.manual_ips.contains(&ip))
+            .map(|(other, _)| other)
+        {
+            bail!("{ip} is already used by {other_hostname}");
+        }
+
+        Ok(())
+    }
+
     pub fn update_client_list(&mut self, hostname: String, action: ClientListAction) {
         let mut client_connections = self.session_config.client_connections.clone();
 
-        let maybe_client_entry = client_connections.entry(hostname);
+        let maybe_client_entry = client_connections.entry(hostname.clone());
 
         let mut updated = false;
+        let mut save_to_disk = true;
+        let mut send_session = true;
         match action {
             ClientListAction::AddIfMissing {
                 trusted,
@@ -209,16 +411,39 @@ impl ServerSessionManager {
                 if let Entry::Vacant(new_entry) = maybe_client_entry {
                     let client_connection_desc = ClientConnectionConfig {
                         display_name: "Unknown".into(),
+                        custom_name: None,
                         current_ip: None,
                         manual_ips: manual_ips.into_iter().collect(),
                         trusted,
                         connection_state: ConnectionState::Disconnected,
+                        last_seen_ms: None,
+                        connection_history: VecDeque::new(),
                     };
                     new_entry.insert(client_connection_desc);
 
                     updated = true;
                 }
             }
+            // Validated by check_manual_ip() by the callers that need to report errors
+            ClientListAction::AddManual { ip, trusted } => match maybe_client_entry {
+                Entry::Occupied(mut entry) => {
+                    updated = entry.get_mut().manual_ips.insert(ip);
+                }
+                Entry::Vacant(new_entry) => {
+                    new_entry.insert(ClientConnectionConfig {
+                        display_name: "Unknown".into(),
+                        custom_name: None,
+                        current_ip: None,
+                        manual_ips: [ip].into_iter().collect(),
+                        trusted,
+                        connection_state: ConnectionState::Disconnected,
+                        last_seen_ms: None,
+                        connection_history: VecDeque::new(),
+                    });
+
+                    updated = true;
+                }
+            },
             ClientListAction::SetDisplayName(name) => {
                 if let Entry::Occupied(mut entry) = maybe_client_entry {
                     entry.get_mut().display_name = name;
@@ -226,6 +451,13 @@ impl ServerSessionManager {
                     updated = true;
                 }
             }
+            ClientListAction::SetCustomName(name) => {
+                if let Entry::Occupied(mut entry) = maybe_client_entry {
+                    entry.get_mut().custom_name = name;
+
+                    updated = true;
+                }
+            }
             ClientListAction::Trust => {
                 if let Entry::Occupied(mut entry) = maybe_client_entry {
                     entry.get_mut().trusted = true;
@@ -256,10 +488,72 @@ impl ServerSessionManager {
                     }
                 }
             }
+            ClientListAction::SetDisconnectReason(reason) => {
+                if let Entry::Occupied(mut entry) = maybe_client_entry {
+                    if let Some(last_entry) = entry
+                        .get_mut()
+                        .connection_history
+                        .back_mut()
+                        .filter(|e| e.disconnected_at_ms.is_none())
+                    {
+                        last_entry.disconnect_reason = Some(reason);
+
+                        updated = true;
+                    }
+                }
+            }
+            ClientListAction::UpdateLastSeen(last_seen_ms) => {
+                if let Entry::Occupied(mut entry) = maybe_client_entry {
+                    entry.get_mut().last_seen_ms = Some(last_seen_ms);
+
+                    // Updated too often to be worth writing to disk or sending the whole session
+                    save_to_disk = false;
+                    send_session = false;
+                    updated = true;
+
+                    alvr_events::send_event(EventType::ClientLastSeen {
+                        hostname: hostname.clone(),
+                        last_seen_ms,
+                    });
+                }
+            }
             ClientListAction::SetConnectionState(state) => {
                 if let Entry::Occupied(mut entry) = maybe_client_entry {
                     if entry.get().connection_state != state {
-                        entry.get_mut().connection_state = state;
+                        let config = entry.get_mut();
+                        config.connection_state = state.clone();
+
+                        let now_ms = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_millis() as u64;
+                        match state {
+                            ConnectionState::Streaming => {
+                                if config.connection_history.len() >= MAX_CONNECTION_HISTORY_ENTRIES
+                                {
+                                    config.connection_history.pop_front();
+                                }
+                                config.connection_history.push_back(ConnectionHistoryEntry {
+                                    connected_at_ms: now_ms,
+                                    disconnected_at_ms: None,
+                                    disconnect_reason: None,
+                                });
+                            }
+                            ConnectionState::Disconnected => {
+                                if let Some(last_entry) = config
+                                    .connection_history
+                                    .back_mut()
+                                    .filter(|e| e.disconnected_at_ms.is_none())
+                                {
+                                    last_entry.disconnected_at_ms = Some(now_ms);
+                                }
+                            }
+                            _ => (),
+                        }
+
+                        if let Some(listener) = &self.connection_state_listener {
+                            listener(&self.settings, &hostname, state);
+                        }
 
                         updated = true;
                     }
@@ -270,10 +564,14 @@ impl ServerSessionManager {
         if updated {
             self.session_config.client_connections = client_connections;
 
-            if let Some(session_path) = &self.session_path {
-                save_session(&self.session_config, session_path).ok();
+            if save_to_disk {
+                if let Some(session_path) = &self.session_path {
+                    save_session(&self.session_config, session_path).ok();
+                }
+            }
+            if send_session {
+                alvr_events::send_event(EventType::Session(Box::new(self.session_config.clone())));
             }
-            alvr_events::send_event(EventType::Session(Box::new(self.session_config.clone())));
         }
     }
 
@@ -3,6 +3,7 @@ use alvr_common::{
     anyhow::{bail, Result},
     ToAny,
 };
+use alvr_packets::RegisteredDriver;
 use serde_json as json;
 use std::{
     collections::{HashMap, HashSet},
@@ -10,6 +11,8 @@ use std::{
     path::PathBuf,
 };
 
+const DRIVER_MANIFEST_FILE: &str = "driver.vrdrivermanifest";
+
 pub fn get_registered_drivers() -> Result<Vec<PathBuf>> {
     Ok(openvrpaths::from_openvr_paths(
         openvrpaths::load_openvr_paths_json()?
@@ -18,6 +21,17 @@ pub fn get_registered_drivers() -> Result<Vec<PathBuf>> {
     ))
 }
 
+// Registered drivers, flagging the ones whose directory was deleted
+pub fn get_driver_list() -> Result<Vec<RegisteredDriver>> {
+    Ok(get_registered_drivers()?
+        .into_iter()
+        .map(|path| RegisteredDriver {
+            exists: path.join(DRIVER_MANIFEST_FILE).is_file(),
+            path,
+        })
+        .collect())
+}
+
 pub fn driver_registration(driver_paths: &[PathBuf], register: bool) -> Result<()> {
     let mut openvr_paths_json = openvrpaths::load_openvr_paths_json()?;
     let paths_json_ref = openvr_paths_json.get_mut("external_drivers").to_any()?;
@@ -27,10 +41,24 @@ pub fn driver_registration(driver_paths: &[PathBuf], register: bool) -> Result<(
         .collect();
 
     if register {
+        for path in driver_paths {
+            if !path.is_dir() {
+                bail!("Driver directory {} does not exist", path.display());
+            }
+            if !path.join(DRIVER_MANIFEST_FILE).is_file() {
+                bail!(
+                    "{} is not a driver directory, {DRIVER_MANIFEST_FILE} is missing",
+                    path.display()
+                );
+            }
+        }
+
         paths.extend(driver_paths.iter().cloned());
     } else {
         for path in driver_paths {
-            paths.remove(path);
+            if !paths.remove(path) {
+                bail!("Driver {} is not registered", path.display());
+            }
         }
     }
 
@@ -44,7 +72,7 @@ pub fn driver_registration(driver_paths: &[PathBuf], register: bool) -> Result<(
 pub fn get_driver_dir_from_registered() -> Result<PathBuf> {
     for dir in get_registered_drivers()? {
         let maybe_driver_name = || -> Result<_> {
-            let manifest_string = fs::read_to_string(dir.join("driver.vrdrivermanifest"))?;
+            let manifest_string = fs::read_to_string(dir.join(DRIVER_MANIFEST_FILE))?;
             let mut manifest_map =
                 json::from_str::<HashMap<String, json::Value>>(&manifest_string)?;
 
@@ -209,6 +209,16 @@ unsigned long long (*GetSerialNumber)(unsigned long long deviceID, char* outStri
 void (*SetOpenvrProps)(void* instancePtr, unsigned long long deviceID);
 void (*RegisterButtons)(void* instancePtr, unsigned long long deviceID);
 void (*WaitForVSync)();
+void (*SaveCapturedFrame)(
+    const char* path,
+    const unsigned char* rgbaData,
+    unsigned int width,
+    unsigned int height,
+    unsigned int rowPitch
+);
+void (*SendPreviewFrame)(
+    const unsigned char* rgbaData, unsigned int width, unsigned int height, unsigned int rowPitch
+);
 
 void CppInit() {
     HookCrashHandler();
@@ -507,10 +517,19 @@ void SetChaperoneArea(float areaWidth, float areaHeight) {
     _SetChaperoneArea(areaWidth, areaHeight);
 }
 
-void CaptureFrame() {
-#ifndef __APPLE__
+void CaptureFrame(const char* pathPrefix) {
+// Only the Linux encoder can save frames
+#ifdef __linux__
+    if (g_driver_provider.hmd && g_driver_provider.hmd->m_encoder) {
+        g_driver_provider.hmd->m_encoder->CaptureFrame(pathPrefix);
+    }
+#endif
+}
+
+void CapturePreviewFrame() {
+#ifdef __linux__
     if (g_driver_provider.hmd && g_driver_provider.hmd->m_encoder) {
-        g_driver_provider.hmd->m_encoder->CaptureFrame();
+        g_driver_provider.hmd->m_encoder->CapturePreviewFrame();
     }
 #endif
 }
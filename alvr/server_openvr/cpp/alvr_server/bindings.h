@@ -132,6 +132,16 @@ extern "C" unsigned long long (*GetSerialNumber)(unsigned long long deviceID, ch
 extern "C" void (*SetOpenvrProps)(void* instancePtr, unsigned long long deviceID);
 extern "C" void (*RegisterButtons)(void* instancePtr, unsigned long long deviceID);
 extern "C" void (*WaitForVSync)();
+extern "C" void (*SaveCapturedFrame)(
+    const char* path,
+    const unsigned char* rgbaData,
+    unsigned int width,
+    unsigned int height,
+    unsigned int rowPitch
+);
+extern "C" void (*SendPreviewFrame)(
+    const unsigned char* rgbaData, unsigned int width, unsigned int height, unsigned int rowPitch
+);
 
 extern "C" void CppInit();
 extern "C" void* CppOpenvrEntryPoint(const char* pInterfaceName, int* pReturnCode);
@@ -163,7 +173,8 @@ extern "C" void InitOpenvrClient();
 extern "C" void ShutdownOpenvrClient();
 extern "C" void SetChaperoneArea(float areaWidth, float areaHeight);
 
-extern "C" void CaptureFrame();
+extern "C" void CaptureFrame(const char* pathPrefix);
+extern "C" void CapturePreviewFrame();
 
 // NalParsing.cpp
 void ParseFrameNals(
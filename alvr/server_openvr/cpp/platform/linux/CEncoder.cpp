@@ -235,12 +235,16 @@ void CEncoder::Run() {
 
             if (m_captureFrame) {
                 m_captureFrame = false;
-                render.CaptureInputFrame(
-                    Settings::Instance().m_captureFrameDir + "/alvr_frame_input.ppm"
-                );
-                render.CaptureOutputFrame(
-                    Settings::Instance().m_captureFrameDir + "/alvr_frame_output.ppm"
-                );
+                std::string pathPrefix;
+                {
+                    std::lock_guard<std::mutex> lock(m_captureFrameMutex);
+                    pathPrefix = m_captureFramePrefix;
+                }
+                render.CaptureInputFrame(pathPrefix + "_input.png");
+                render.CaptureOutputFrame(pathPrefix + "_output.png");
+            }
+            if (m_capturePreviewFrame.exchange(false)) {
+                render.CaptureOutputPreview();
             }
 
             render.Render(frame_info.image, frame_info.semaphore_value);
@@ -316,4 +320,10 @@ void CEncoder::OnPacketLoss() { m_scheduler.OnPacketLoss(); }
 
 void CEncoder::InsertIDR() { m_scheduler.InsertIDR(); }
 
-void CEncoder::CaptureFrame() { m_captureFrame = true; }
+void CEncoder::CaptureFrame(const std::string& pathPrefix) {
+    std::lock_guard<std::mutex> lock(m_captureFrameMutex);
+    m_captureFramePrefix = pathPrefix;
+    m_captureFrame = true;
+}
+
+void CEncoder::CapturePreviewFrame() { m_capturePreviewFrame = true; }
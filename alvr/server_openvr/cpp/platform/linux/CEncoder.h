@@ -4,7 +4,9 @@
 #include "shared/threadtools.h"
 #include <atomic>
 #include <memory>
+#include <mutex>
 #include <poll.h>
+#include <string>
 #include <sys/types.h>
 
 class PoseHistory;
@@ -21,7 +23,8 @@ public:
     void OnPacketLoss();
     void InsertIDR();
     bool IsConnected() { return m_connected; }
-    void CaptureFrame();
+    void CaptureFrame(const std::string& pathPrefix);
+    void CapturePreviewFrame();
 
 private:
     void GetFds(int client, int (*fds)[6]);
@@ -33,4 +36,7 @@ private:
     int m_fds[6];
     bool m_connected = false;
     std::atomic_bool m_captureFrame = false;
+    std::mutex m_captureFrameMutex;
+    std::string m_captureFramePrefix;
+    std::atomic_bool m_capturePreviewFrame = false;
 };
@@ -1,4 +1,5 @@
 #include "Renderer.h"
+#include "alvr_server/bindings.h"
 
 #include <algorithm>
 #include <array>
@@ -579,7 +580,15 @@ void Renderer::Render(uint32_t index, uint64_t waitValue) {
             m_images[index].layout,
             m_imageSize.width,
             m_imageSize.height,
-            m_inputImageCapture
+            [&](const unsigned char* rgbaData, uint32_t rowPitch) {
+                SaveCapturedFrame(
+                    m_inputImageCapture.c_str(),
+                    rgbaData,
+                    m_imageSize.width,
+                    m_imageSize.height,
+                    rowPitch
+                );
+            }
         );
         m_inputImageCapture.clear();
     }
@@ -731,16 +740,28 @@ Renderer::Timestamps Renderer::GetTimestamps() {
     VK_CHECK(d.vkGetCalibratedTimestampsEXT(m_dev, 1, &timestampInfo, &timestamp, &deviation));
     timestamp *= m_timestampPeriod;
 
-    if (!m_outputImageCapture.empty()) {
+    if (!m_outputImageCapture.empty() || m_outputPreviewCapture) {
+        uint32_t width = m_output.imageInfo.extent.width;
+        uint32_t height = m_output.imageInfo.extent.height;
         dumpImage(
             m_output.image,
             m_output.view,
             m_output.layout,
-            m_output.imageInfo.extent.width,
-            m_output.imageInfo.extent.height,
-            m_outputImageCapture
+            width,
+            height,
+            [&](const unsigned char* rgbaData, uint32_t rowPitch) {
+                if (!m_outputImageCapture.empty()) {
+                    SaveCapturedFrame(
+                        m_outputImageCapture.c_str(), rgbaData, width, height, rowPitch
+                    );
+                }
+                if (m_outputPreviewCapture) {
+                    SendPreviewFrame(rgbaData, width, height, rowPitch);
+                }
+            }
         );
         m_outputImageCapture.clear();
+        m_outputPreviewCapture = false;
     }
 
     return { timestamp, queries[0], queries[1] };
@@ -750,6 +771,8 @@ void Renderer::CaptureInputFrame(const std::string& filename) { m_inputImageCapt
 
 void Renderer::CaptureOutputFrame(const std::string& filename) { m_outputImageCapture = filename; }
 
+void Renderer::CaptureOutputPreview() { m_outputPreviewCapture = true; }
+
 std::string Renderer::result_to_str(VkResult result) {
     switch (result) {
 #define VAL(x)                                                                                     \
@@ -870,7 +893,7 @@ void Renderer::dumpImage(
     VkImageLayout imageLayout,
     uint32_t width,
     uint32_t height,
-    const std::string& filename
+    const std::function<void(const unsigned char* rgbaData, uint32_t rowPitch)>& onCaptured
 ) {
     VkImageCreateInfo imageInfo = {};
     imageInfo.sType = VK_STRUCTURE_TYPE_IMAGE_CREATE_INFO;
@@ -1064,22 +1087,8 @@ void Renderer::dumpImage(
     VK_CHECK(vkMapMemory(m_dev, dstMemory, 0, VK_WHOLE_SIZE, 0, (void**)&imageData));
     imageData += layout.offset;
 
-    std::ofstream file(filename, std::ios::out | std::ios::binary);
-
-    // PPM header
-    file << "P6\n" << width << "\n" << height << "\n" << 255 << "\n";
-
-    // PPM binary pixel data
-    for (uint32_t y = 0; y < height; y++) {
-        uint32_t* row = (uint32_t*)imageData;
-        for (uint32_t x = 0; x < width; x++) {
-            file.write((char*)row++, 3);
-        }
-        imageData += layout.rowPitch;
-    }
-    file.close();
-
-    std::cout << "Image saved to \"" << filename << "\"" << std::endl;
+    // The pixels must be copied, the memory is freed right after
+    onCaptured((const unsigned char*)imageData, layout.rowPitch);
 
     vkUnmapMemory(m_dev, dstMemory);
     vkFreeMemory(m_dev, dstMemory, nullptr);
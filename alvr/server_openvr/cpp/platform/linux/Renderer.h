@@ -1,6 +1,7 @@
 #pragma once
 
 #include <array>
+#include <functional>
 #include <iostream>
 #include <string>
 #include <vector>
@@ -80,6 +81,7 @@ public:
 
     void CaptureInputFrame(const std::string& filename);
     void CaptureOutputFrame(const std::string& filename);
+    void CaptureOutputPreview();
 
     static std::string result_to_str(VkResult result);
 
@@ -108,7 +110,7 @@ public:
         VkImageLayout imageLayout,
         uint32_t width,
         uint32_t height,
-        const std::string& filename
+        const std::function<void(const unsigned char* rgbaData, uint32_t rowPitch)>& onCaptured
     );
     uint32_t memoryTypeIndex(VkMemoryPropertyFlags properties, uint32_t typeBits) const;
 
@@ -150,6 +152,7 @@ public:
 
     std::string m_inputImageCapture;
     std::string m_outputImageCapture;
+    bool m_outputPreviewCapture = false;
 };
 
 class RenderPipeline {
@@ -23,7 +23,8 @@ use alvr_packets::{ButtonValue, Haptics};
 use alvr_server_core::{HandType, ServerCoreContext, ServerCoreEvent};
 use alvr_session::{CodecType, ControllersConfig};
 use std::{
-    ffi::{c_char, c_void, CString, OsStr},
+    ffi::{c_char, c_void, CStr, CString, OsStr},
+    path::PathBuf,
     ptr,
     sync::{mpsc, Once},
     thread,
@@ -236,7 +237,12 @@ fn event_loop(events_receiver: mpsc::Receiver<ServerCoreEvent>) {
                     }
                 }
                 ServerCoreEvent::RequestIDR => unsafe { RequestIDR() },
-                ServerCoreEvent::CaptureFrame => unsafe { CaptureFrame() },
+                ServerCoreEvent::CaptureFrame { path_prefix } => {
+                    if let Ok(path_prefix) = CString::new(path_prefix.to_string_lossy().as_ref()) {
+                        unsafe { CaptureFrame(path_prefix.as_ptr()) };
+                    }
+                }
+                ServerCoreEvent::CapturePreviewFrame => unsafe { CapturePreviewFrame() },
                 ServerCoreEvent::GameRenderLatencyFeedback(game_latency) => {
                     if cfg!(target_os = "linux") && game_latency.as_secs_f32() > 0.25 {
                         let now = Instant::now();
@@ -375,6 +381,43 @@ extern "C" fn report_present(timestamp_ns: u64, offset_ns: u64) {
     }
 }
 
+// The rows can be padded, the alpha channel is dropped
+fn rgba_rows_to_rgb(rgba_ptr: *const u8, width: u32, height: u32, row_pitch: u32) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(width as usize * height as usize * 3);
+    for y in 0..height as usize {
+        let row = unsafe {
+            std::slice::from_raw_parts(rgba_ptr.add(y * row_pitch as usize), width as usize * 4)
+        };
+        for pixel in row.chunks_exact(4) {
+            rgb.extend_from_slice(&pixel[..3]);
+        }
+    }
+
+    rgb
+}
+
+extern "C" fn save_captured_frame(
+    path_ptr: *const c_char,
+    rgba_ptr: *const u8,
+    width: u32,
+    height: u32,
+    row_pitch: u32,
+) {
+    let path = unsafe { CStr::from_ptr(path_ptr) }
+        .to_string_lossy()
+        .into_owned();
+    let rgb = rgba_rows_to_rgb(rgba_ptr, width, height, row_pitch);
+
+    alvr_server_core::save_captured_frame(PathBuf::from(path), width, height, rgb);
+}
+
+extern "C" fn send_preview_frame(rgba_ptr: *const u8, width: u32, height: u32, row_pitch: u32) {
+    if let Some(context) = &*SERVER_CORE_CONTEXT.read() {
+        let rgb = rgba_rows_to_rgb(rgba_ptr, width, height, row_pitch);
+        context.send_video_preview_frame(width, height, rgb);
+    }
+}
+
 extern "C" fn wait_for_vsync() {
     // Default 120Hz-ish wait if StatisticsManager isn't up.
     // We use 120Hz-ish so that SteamVR doesn't accidentally get
@@ -484,6 +527,8 @@ pub unsafe extern "C" fn HmdDriverFactory(
             ReportComposed = Some(report_composed);
             ReportPresent = Some(report_present);
             WaitForVSync = Some(wait_for_vsync);
+            SaveCapturedFrame = Some(save_captured_frame);
+            SendPreviewFrame = Some(send_preview_frame);
             ShutdownRuntime = Some(shutdown_driver);
 
             CppInit();
@@ -6,16 +6,18 @@ pub use settings_schema;
 use alvr_common::{
     anyhow::{bail, Result},
     semver::Version,
-    ConnectionState, ToAny, ALVR_VERSION,
+    ConnectionState, DisconnectReason, ToAny, ALVR_VERSION,
 };
 use serde::{Deserialize, Serialize};
 use serde_json as json;
 use settings_schema::{NumberType, SchemaNode};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     net::IpAddr,
 };
 
+pub const MAX_CONNECTION_HISTORY_ENTRIES: usize = 32;
+
 // SessionSettings is similar to Settings but it contains every branch, even unused ones. This is
 // the settings representation that the UI uses.
 pub type SessionSettings = settings::SettingsDefault;
@@ -119,15 +121,39 @@ pub struct OpenvrConfig {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ClientConnectionConfig {
     pub display_name: String,
+    // Set by the user, shown instead of display_name, which is reported by the client
+    #[serde(default)]
+    pub custom_name: Option<String>,
     pub current_ip: Option<IpAddr>,
     pub manual_ips: HashSet<IpAddr>,
     pub trusted: bool,
     pub connection_state: ConnectionState,
+    // Milliseconds since the UNIX epoch of the last packet received from the client, updated
+    // every few seconds while streaming
+    #[serde(default)]
+    pub last_seen_ms: Option<u64>,
+    // Oldest entries are dropped after MAX_CONNECTION_HISTORY_ENTRIES
+    #[serde(default)]
+    pub connection_history: VecDeque<ConnectionHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConnectionHistoryEntry {
+    // Milliseconds since the UNIX epoch
+    pub connected_at_ms: u64,
+    // None if the client is still connected or the server was closed abruptly
+    pub disconnected_at_ms: Option<u64>,
+    #[serde(default)]
+    pub disconnect_reason: Option<DisconnectReason>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SessionConfig {
     pub server_version: Version,
+    // Incremented on every settings write, to detect concurrent modifications. Client list
+    // updates are not counted, so they never invalidate a pending settings write
+    #[serde(default)]
+    pub revision: u64,
     pub openvr_config: OpenvrConfig,
     // The hashmap key is the hostname
     pub client_connections: HashMap<String, ClientConnectionConfig>,
@@ -138,6 +164,7 @@ impl Default for SessionConfig {
     fn default() -> Self {
         Self {
             server_version: ALVR_VERSION.clone(),
+            revision: 0,
             openvr_config: OpenvrConfig {
                 // avoid realistic resolutions, as on first start, on Linux, it
                 // could trigger direct mode on an existing monitor
@@ -977,6 +977,16 @@ pub struct VMCConfig {
     pub orientation_correction: bool,
 }
 
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+#[schema(collapsible)]
+pub struct OscOutputConfig {
+    pub host: String,
+    pub port: u16,
+    #[schema(strings(help = "Button changes are always sent immediately."))]
+    #[schema(gui(slider(min = 1, max = 120)), suffix = "Hz")]
+    pub max_pose_rate_hz: u32,
+}
+
 #[derive(SettingsSchema, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum ControllersEmulationMode {
     #[schema(strings(display_name = "Rift S Touch"))]
@@ -1295,6 +1305,13 @@ Tilted: the world gets tilted when long pressing the oculus button. This is usef
     #[schema(strings(display_name = "VMC"))]
     pub vmc: Switch<VMCConfig>,
 
+    #[schema(flag = "steamvr-restart")]
+    #[schema(strings(
+        display_name = "OSC output",
+        help = "Send headset and controller poses and button values over OSC, with addresses like /alvr/user/head/pose and /alvr/user/hand/left/input/trigger/value"
+    ))]
+    pub osc_output: Switch<OscOutputConfig>,
+
     #[schema(strings(
         help = "Maximum prediction for head and controllers. Used to avoid too much jitter during loading."
     ))]
@@ -1320,6 +1337,15 @@ pub struct DiscoveryConfig {
     pub auto_trust_clients: bool,
 }
 
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct WebServerTlsConfig {
+    #[schema(strings(help = "Path to the PEM encoded certificate chain"))]
+    pub cert_path: String,
+
+    #[schema(strings(help = "Path to the PEM encoded private key"))]
+    pub key_path: String,
+}
+
 #[derive(SettingsSchema, Serialize, Deserialize, Clone, Copy)]
 pub enum SocketBufferSize {
     Default,
@@ -1327,6 +1353,28 @@ pub enum SocketBufferSize {
     Custom(#[schema(suffix = "B")] u32),
 }
 
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct MqttCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+#[schema(collapsible)]
+pub struct MqttConfig {
+    pub broker_host: String,
+    pub broker_port: u16,
+    pub credentials: Option<MqttCredentials>,
+    pub client_id: String,
+    #[schema(strings(
+        help = "Messages are published to <prefix>/clients/<hostname>/connection_state, <prefix>/battery/hmd and <prefix>/statistics"
+    ))]
+    pub topic_prefix: String,
+    pub publish_connection_state: bool,
+    pub publish_battery: bool,
+    pub publish_statistics: bool,
+}
+
 #[derive(SettingsSchema, Serialize, Deserialize, Clone)]
 pub struct ConnectionConfig {
     #[schema(strings(
@@ -1382,6 +1430,12 @@ TCP: Slower than UDP, but more stable. Pick this if you experience video or audi
     #[schema(flag = "real-time")]
     pub allow_untrusted_http: bool,
 
+    #[schema(strings(
+        help = "Browser origins (for example https://example.com) allowed to make cross-origin requests to the web server. If empty, any origin is allowed only with 'Allow untrusted HTTP'."
+    ))]
+    #[schema(flag = "real-time")]
+    pub web_server_allowed_origins: Vec<String>,
+
     #[schema(strings(
         help = r#"If the client, server or the network discarded one packet, discard packets until a IDR packet is found.
 For now works only on Windows+Nvidia"#
@@ -1392,7 +1446,95 @@ For now works only on Windows+Nvidia"#
     pub packet_size: i32,
 
     pub stream_port: u16,
+
+    #[schema(strings(
+        help = "IP address the web server listens on. Use 127.0.0.1 to allow only connections from this PC."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub web_server_bind: String,
+
     pub web_server_port: u16,
+
+    #[schema(strings(
+        help = "If the web server port is already in use, try the next ports. The dashboard still connects to the configured port, so change it to the one reported in the log"
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub web_server_port_fallback: bool,
+
+    #[schema(strings(
+        help = "Number of worker threads of the web server. If unset, one thread per CPU core is used, which is enough for most setups. Increase it if many dashboards or video mirror clients are connected at the same time."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub web_server_threads: Option<usize>,
+
+    #[schema(strings(
+        display_name = "Web server TLS",
+        help = r#"Serve the web server over HTTPS/WSS. If the certificate cannot be loaded, plain HTTP is used instead.
+Connections from the local machine can still use plain HTTP."#
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub web_server_tls: Switch<WebServerTlsConfig>,
+
+    #[schema(strings(
+        help = r#"If set, web server requests must include the header "Authorization: Bearer <token>".
+WebSocket connections can pass it as the "token" query parameter instead."#
+    ))]
+    #[schema(flag = "real-time")]
+    pub web_server_auth_token: Option<String>,
+
+    #[schema(strings(
+        display_name = "WebSocket heartbeat interval",
+        help = "Interval between pings sent to web server WebSocket connections. Connections that don't answer before the next ping are closed."
+    ))]
+    #[schema(gui(slider(min = 1, max = 120)), suffix = "s")]
+    pub websocket_heartbeat_interval_s: u64,
+
+    #[schema(strings(
+        help = "Number of events buffered for each /api/events WebSocket client before they start getting dropped."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub web_server_events_capacity: usize,
+
+    #[schema(strings(
+        help = "Number of statistics events buffered for each /api/statistics WebSocket client before they start getting dropped."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub web_server_statistics_capacity: usize,
+
+    #[schema(strings(
+        help = "Number of video packets buffered for each /api/video-mirror WebSocket client before they start getting dropped."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub web_server_video_mirror_capacity: usize,
+
+    #[schema(strings(
+        display_name = "Web server max request body size",
+        help = "Larger request bodies sent to /api/dashboard-request and /api/set-buttons are rejected."
+    ))]
+    #[schema(suffix = "MB")]
+    #[schema(flag = "real-time")]
+    pub web_server_max_request_body_mb: u64,
+
+    #[schema(strings(
+        display_name = "Web server max button updates per second",
+        help = "Calls to /api/set-buttons over this rate are answered with 429. Their values are still applied later, keeping only the latest value of each button."
+    ))]
+    #[schema(flag = "real-time")]
+    pub web_server_max_button_updates_per_s: Switch<u32>,
+
+    #[schema(strings(
+        help = r#"When a client connection state changes, a POST request is sent to this URL with a JSON body like {"hostname": "...", "state": "Connected", "timestamp": "..."}"#
+    ))]
+    #[schema(flag = "real-time")]
+    pub connection_state_webhook_url: Option<String>,
+
+    #[schema(strings(
+        display_name = "MQTT",
+        help = "Publish connection state, battery and statistics to an MQTT broker"
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub mqtt: Switch<MqttConfig>,
+
     pub osc_local_port: u16,
 
     #[schema(strings(display_name = "Streamer send buffer size"))]
@@ -1454,6 +1596,60 @@ pub struct RawEventsConfig {
     pub hide_spammy_events: bool,
 }
 
+#[derive(SettingsSchema, Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[schema(gui = "button_group")]
+pub enum LogFileFormat {
+    Text,
+    #[schema(strings(display_name = "JSON lines"))]
+    JsonLines,
+}
+
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct SessionLogRotationConfig {
+    #[schema(strings(display_name = "Max file size"))]
+    #[schema(suffix = "MB")]
+    pub max_file_size_mb: u64,
+
+    #[schema(strings(help = "Number of previous log files to keep (session_log.txt.1, .2, ...)"))]
+    pub max_archives: usize,
+
+    #[schema(strings(
+        display_name = "Max total size",
+        help = "Older log files are deleted when the total size of all log files exceeds this value"
+    ))]
+    #[schema(suffix = "MB")]
+    pub max_total_size_mb: u64,
+}
+
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct DailyLogFilesConfig {
+    #[schema(strings(help = "Log files older than this number of days are deleted"))]
+    #[schema(suffix = " days")]
+    pub retention_days: u64,
+
+    pub format: LogFileFormat,
+}
+
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct RemoteLogEndpointConfig {
+    #[schema(strings(help = "Address of the log collector, for example 192.168.1.10:5140"))]
+    pub address: String,
+
+    #[schema(strings(help = "TCP reconnects automatically if the collector is restarted"))]
+    pub protocol: SocketProtocol,
+
+    pub format: LogFileFormat,
+}
+
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct WebServerAccessLogConfig {
+    #[schema(strings(display_name = "Include /api/ping"))]
+    #[schema(flag = "real-time")]
+    pub include_ping: bool,
+}
+
+pub const DEFAULT_LOG_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
 #[derive(SettingsSchema, Serialize, Deserialize, Clone)]
 pub struct LoggingConfig {
     #[schema(strings(help = "Notification tips teach you how to use ALVR"))]
@@ -1474,6 +1670,62 @@ pub struct LoggingConfig {
     #[schema(strings(help = "Write logs into the session_log.txt file."))]
     pub log_to_disk: bool,
 
+    #[schema(strings(
+        help = "Keep the logs of previous sessions in session_log.txt instead of clearing it at startup."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub append_session_log: bool,
+
+    #[schema(flag = "steamvr-restart")]
+    pub session_log_rotation: Switch<SessionLogRotationConfig>,
+
+    #[schema(strings(
+        help = "JSON lines writes one object per line with timestamp, level, target and message fields, for log aggregators."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub session_log_format: LogFileFormat,
+
+    #[schema(strings(
+        help = "Also write logs into one file per day (session_log_YYYY-MM-DD.txt) in the log directory."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub daily_log_files: Switch<DailyLogFilesConfig>,
+
+    #[schema(strings(
+        help = "Number of recent log entries kept in memory, so the dashboard can show them when it connects."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub log_history_size: usize,
+
+    #[schema(strings(
+        help = "Number of recent events of each type (session, statistics, ...) sent to the dashboard when it connects."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub event_replay_size: usize,
+
+    #[schema(strings(
+        help = "strftime pattern used for log timestamps, for example %Y-%m-%d %H:%M:%S%.3f"
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub log_timestamp_format: String,
+
+    #[schema(strings(display_name = "Log timestamps in UTC"))]
+    #[schema(flag = "steamvr-restart")]
+    pub log_timestamps_utc: bool,
+
+    #[schema(strings(
+        display_name = "Redact IP addresses",
+        help = "Replace IP addresses in log messages with a hash, so logs can be shared publicly. Disable for local debugging"
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub redact_ip_addresses: bool,
+
+    #[schema(strings(
+        help = "Send each log line to a remote collector. Lines are dropped if the collector can't keep up"
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub remote_log_endpoint: Switch<RemoteLogEndpointConfig>,
+
     #[schema(flag = "real-time")]
     pub log_tracking: bool,
 
@@ -1483,6 +1735,18 @@ pub struct LoggingConfig {
     #[schema(flag = "real-time")]
     pub log_haptics: bool,
 
+    #[schema(strings(
+        help = "Log method, path, status code and response time of each web server request, at debug level."
+    ))]
+    #[schema(flag = "real-time")]
+    pub web_server_access_log: Switch<WebServerAccessLogConfig>,
+
+    #[schema(strings(
+        help = "Log level for modules starting with the given path (for example alvr_server_core::connection). Other modules use the default level."
+    ))]
+    #[schema(flag = "steamvr-restart")]
+    pub module_log_levels: Vec<(String, LogSeverity)>,
+
     #[cfg_attr(not(debug_assertions), schema(flag = "hidden"))]
     #[schema(strings(help = "These settings enable extra spammy logs for debugging purposes."))]
     pub debug_groups: DebugGroupsConfig,
@@ -1501,14 +1765,62 @@ pub struct RollingVideoFilesConfig {
     pub duration_s: u64,
 }
 
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct SplitVideoFilesConfig {
+    #[schema(strings(display_name = "Max file size"))]
+    #[schema(suffix = "MB")]
+    pub max_file_size_mb: u64,
+}
+
+#[derive(SettingsSchema, Serialize, Deserialize, Clone)]
+pub struct RecordingCleanupConfig {
+    #[schema(strings(display_name = "Max age"))]
+    #[schema(suffix = " days")]
+    pub max_age_days: Option<u64>,
+
+    #[schema(strings(
+        display_name = "Max total size",
+        help = "The oldest recordings are deleted until the total size is under this limit"
+    ))]
+    #[schema(suffix = "MB")]
+    pub max_total_size_mb: Option<u64>,
+}
+
 #[derive(SettingsSchema, Serialize, Deserialize, Clone)]
 pub struct CaptureConfig {
     #[schema(strings(display_name = "Start video recording at client connection"))]
     pub startup_video_recording: bool,
 
+    #[schema(strings(
+        help = "If disabled, the recording continues in the same file when the client reconnects"
+    ))]
+    pub stop_recording_on_disconnect: bool,
+
     pub rolling_video_files: Switch<RollingVideoFilesConfig>,
 
-    #[schema(flag = "steamvr-restart")]
+    #[schema(strings(
+        help = "Continue the recording in a new numbered file when the current one exceeds this size"
+    ))]
+    pub split_video_files: Switch<SplitVideoFilesConfig>,
+
+    #[schema(strings(
+        help = "Directory where video recordings are saved. If unset, the log directory is used"
+    ))]
+    #[schema(flag = "real-time")]
+    pub recording_dir: Option<String>,
+
+    #[schema(strings(
+        help = "When a recording starts, delete the old .h264, .h265 and .av1 files in the recording directory. The recording in progress is never deleted"
+    ))]
+    #[schema(flag = "real-time")]
+    pub recording_cleanup: Switch<RecordingCleanupConfig>,
+
+    #[schema(strings(
+        help = "Name of the recording file, without extension. Supported tokens: {timestamp}, {hostname}, {index}"
+    ))]
+    #[schema(flag = "real-time")]
+    pub recording_filename_template: String,
+
     pub capture_frame_dir: String,
 }
 
@@ -1929,6 +2241,15 @@ pub fn session_settings_default() -> SettingsDefault {
                     orientation_correction: true,
                 },
             },
+            osc_output: SwitchDefault {
+                enabled: false,
+                content: OscOutputConfigDefault {
+                    gui_collapsed: true,
+                    host: "127.0.0.1".into(),
+                    port: 9100,
+                    max_pose_rate_hz: 30,
+                },
+            },
             controllers: SwitchDefault {
                 enabled: true,
                 content: ControllersConfigDefault {
@@ -2073,7 +2394,57 @@ pub fn session_settings_default() -> SettingsDefault {
                 },
             },
             wired_client_autolaunch: true,
+            web_server_bind: "0.0.0.0".into(),
             web_server_port: 8082,
+            web_server_port_fallback: false,
+            web_server_threads: OptionalDefault {
+                set: false,
+                content: 4,
+            },
+            web_server_tls: SwitchDefault {
+                enabled: false,
+                content: WebServerTlsConfigDefault {
+                    cert_path: "".into(),
+                    key_path: "".into(),
+                },
+            },
+            web_server_auth_token: OptionalDefault {
+                set: false,
+                content: "".into(),
+            },
+            websocket_heartbeat_interval_s: 15,
+            web_server_events_capacity: 256,
+            web_server_statistics_capacity: 64,
+            web_server_video_mirror_capacity: 1024,
+            web_server_max_request_body_mb: 4,
+            web_server_max_button_updates_per_s: SwitchDefault {
+                enabled: true,
+                content: 100,
+            },
+            connection_state_webhook_url: OptionalDefault {
+                set: false,
+                content: "".into(),
+            },
+            mqtt: SwitchDefault {
+                enabled: false,
+                content: MqttConfigDefault {
+                    gui_collapsed: true,
+                    broker_host: "localhost".into(),
+                    broker_port: 1883,
+                    credentials: OptionalDefault {
+                        set: false,
+                        content: MqttCredentialsDefault {
+                            username: "".into(),
+                            password: "".into(),
+                        },
+                    },
+                    client_id: "alvr".into(),
+                    topic_prefix: "alvr".into(),
+                    publish_connection_state: true,
+                    publish_battery: true,
+                    publish_statistics: false,
+                },
+            },
             stream_port: 9944,
             osc_local_port: 9942,
             dscp: OptionalDefault {
@@ -2099,6 +2470,11 @@ pub fn session_settings_default() -> SettingsDefault {
             enable_on_connect_script: false,
             enable_on_disconnect_script: false,
             allow_untrusted_http: false,
+            web_server_allowed_origins: VectorDefault {
+                gui_collapsed: true,
+                element: "".into(),
+                content: vec![],
+            },
             packet_size: 1400,
             statistics_history_size: 256,
         },
@@ -2111,9 +2487,61 @@ pub fn session_settings_default() -> SettingsDefault {
                     },
                 },
                 log_to_disk: cfg!(debug_assertions),
+                append_session_log: true,
+                session_log_rotation: SwitchDefault {
+                    enabled: true,
+                    content: SessionLogRotationConfigDefault {
+                        max_file_size_mb: 10,
+                        max_archives: 3,
+                        max_total_size_mb: 50,
+                    },
+                },
+                daily_log_files: SwitchDefault {
+                    enabled: false,
+                    content: DailyLogFilesConfigDefault {
+                        retention_days: 7,
+                        format: LogFileFormatDefault {
+                            variant: LogFileFormatDefaultVariant::Text,
+                        },
+                    },
+                },
+                session_log_format: LogFileFormatDefault {
+                    variant: LogFileFormatDefaultVariant::Text,
+                },
+                module_log_levels: DictionaryDefault {
+                    gui_collapsed: true,
+                    key: "alvr_server_core::connection".into(),
+                    value: LogSeverityDefault {
+                        variant: LogSeverityDefaultVariant::Debug,
+                    },
+                    content: vec![],
+                },
+                log_history_size: 500,
+                event_replay_size: 4,
+                log_timestamp_format: DEFAULT_LOG_TIMESTAMP_FORMAT.into(),
+                log_timestamps_utc: false,
+                redact_ip_addresses: true,
+                remote_log_endpoint: SwitchDefault {
+                    enabled: false,
+                    content: RemoteLogEndpointConfigDefault {
+                        address: "127.0.0.1:5140".into(),
+                        protocol: SocketProtocolDefault {
+                            variant: SocketProtocolDefaultVariant::Tcp,
+                        },
+                        format: LogFileFormatDefault {
+                            variant: LogFileFormatDefaultVariant::Text,
+                        },
+                    },
+                },
                 log_button_presses: false,
                 log_tracking: false,
                 log_haptics: false,
+                web_server_access_log: SwitchDefault {
+                    enabled: false,
+                    content: WebServerAccessLogConfigDefault {
+                        include_ping: false,
+                    },
+                },
                 notification_level: LogSeverityDefault {
                     variant: if cfg!(debug_assertions) {
                         LogSeverityDefaultVariant::Info
@@ -2147,10 +2575,35 @@ pub fn session_settings_default() -> SettingsDefault {
             },
             capture: CaptureConfigDefault {
                 startup_video_recording: false,
+                stop_recording_on_disconnect: true,
                 rolling_video_files: SwitchDefault {
                     enabled: false,
                     content: RollingVideoFilesConfigDefault { duration_s: 5 },
                 },
+                split_video_files: SwitchDefault {
+                    enabled: false,
+                    content: SplitVideoFilesConfigDefault {
+                        max_file_size_mb: 1024,
+                    },
+                },
+                recording_dir: OptionalDefault {
+                    set: false,
+                    content: "".into(),
+                },
+                recording_cleanup: SwitchDefault {
+                    enabled: false,
+                    content: RecordingCleanupConfigDefault {
+                        max_age_days: OptionalDefault {
+                            set: true,
+                            content: 30,
+                        },
+                        max_total_size_mb: OptionalDefault {
+                            set: false,
+                            content: 10240,
+                        },
+                    },
+                },
+                recording_filename_template: "recording.{timestamp}".into(),
                 capture_frame_dir: if !cfg!(target_os = "linux") {
                     "/tmp".into()
                 } else {
@@ -1,10 +1,12 @@
 #!/usr/bin/env bash
 # Basic script to add / remove firewall configuration for ALVR
-# Usage: ./alvr_fw_config.sh add|remove
+# Usage: ./alvr_fw_config.sh add|remove|status
 # Exit codes:
 # 1 - Invalid command
 # 2 - Invalid action
 # 3 - Failed to copy UFW configuration
+# 4 - Root privileges are required to read the firewall status
+# 5 - Firewall rules are not set (status)
 # 99 - Firewall not found
 # 126 - pkexec failed - Request dismissed
 
@@ -27,6 +29,10 @@ firewalld_cfg() {
             if firewall-cmd --zone="${zone}" --list-services --permanent | grep 'alvr' >/dev/null 2>&1; then
                 firewall-cmd --zone="${zone}" --remove-service='alvr' --permanent
             fi
+        elif [ "${1}" == 'status' ]; then
+            if ! firewall-cmd --zone="${zone}" --list-services | grep 'alvr' >/dev/null 2>&1; then
+                exit 5
+            fi
         else
             exit 2
         fi
@@ -34,6 +40,13 @@ firewalld_cfg() {
 }
 
 ufw_cfg() {
+    if [ "${1}" == 'status' ]; then
+        if ! ufw status | grep 'alvr' >/dev/null 2>&1; then
+            exit 5
+        fi
+        return
+    fi
+
     # Try and install the application file
     if ! ufw app info 'alvr'; then
         # Pull application file from local build first if the script lives inside it
@@ -86,6 +99,10 @@ iptables_cfg() {
             iptables -D INPUT -p udp --dport 9944 -j ACCEPT
             iptables-save >/etc/iptables/rules.v4
         fi
+    elif [ "${1}" == 'status' ]; then
+        if [ "$first_port_match_count" != "4" ] || [ "$second_port_match_count" != "4" ]; then
+            exit 5
+        fi
     else
         exit 2
     fi
@@ -93,7 +110,16 @@ iptables_cfg() {
 
 main() {
     # If we're not root use pkexec for GUI prompt
-    if [ "${USER}" == 'root' ]; then
+    if [ "${1,,}" == 'status' ] && [ "${USER}" != 'root' ]; then
+        # Only firewalld can be queried without root, don't prompt just to read the status
+        if which firewall-cmd >/dev/null 2>&1 && firewall-cmd --state >/dev/null 2>&1; then
+            firewalld_cfg 'status'
+        elif which ufw >/dev/null 2>&1 || which iptables >/dev/null 2>&1; then
+            exit 4
+        else
+            exit 99
+        fi
+    elif [ "${USER}" == 'root' ]; then
         # Check if firewall-cmd exists and firewalld is running
         if which firewall-cmd >/dev/null 2>&1 && firewall-cmd --state >/dev/null 2>&1; then
             firewalld_cfg "${1,,}"